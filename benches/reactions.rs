@@ -0,0 +1,201 @@
+use bevy_app::{App, Update};
+use bevy_ecs::prelude::*;
+use bevy_mod_reaction::{Reaction, ReactionPlugin, ReactiveQuery, Scope};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+#[derive(Component)]
+struct Health(i32);
+
+#[derive(Resource)]
+struct Tick(i32);
+
+fn setup(reactions: usize) -> App {
+    setup_with(ReactionPlugin::new(), reactions)
+}
+
+fn setup_with(plugin: ReactionPlugin, reactions: usize) -> App {
+    setup_reactions(plugin, reactions, |reaction| reaction)
+}
+
+fn setup_reactions(
+    plugin: ReactionPlugin,
+    reactions: usize,
+    mut f: impl FnMut(Reaction) -> Reaction,
+) -> App {
+    let mut app = App::new();
+    app.add_plugins(plugin);
+
+    for i in 0..100 {
+        app.world_mut().spawn(Health(i));
+    }
+    for _ in 0..reactions {
+        app.world_mut()
+            .spawn(f(Reaction::new(|_: In<Scope>, query: Query<&Health>| {
+                for health in &query {
+                    criterion::black_box(health.0);
+                }
+            })));
+    }
+
+    // Run once to initialize every reaction.
+    app.update();
+    app
+}
+
+fn idle_reactions(c: &mut Criterion) {
+    let mut app = setup(10_000);
+    c.bench_function("10k idle reactions", |b| b.iter(|| app.update()));
+}
+
+fn idle_reactions_observed(c: &mut Criterion) {
+    let mut app = setup_reactions(ReactionPlugin::new().observed(), 10_000, |reaction| {
+        reaction.wake_on::<Health>()
+    });
+    c.bench_function("10k idle reactions (observed)", |b| b.iter(|| app.update()));
+}
+
+fn idle_reactions_ungrouped(c: &mut Criterion) {
+    let mut app = setup(1_000);
+    c.bench_function("1k idle reactions", |b| b.iter(|| app.update()));
+}
+
+fn idle_reactions_grouped(c: &mut Criterion) {
+    let mut i = 0;
+    let mut app = setup_reactions(ReactionPlugin::new(), 1_000, |reaction| {
+        i += 1;
+        reaction.in_group(i % 10)
+    });
+    c.bench_function("1k idle reactions (10 groups)", |b| b.iter(|| app.update()));
+}
+
+fn changed_reactions(c: &mut Criterion) {
+    let mut app = setup(10_000);
+    app.add_systems(Update, |mut query: Query<&mut Health>| {
+        if let Some(mut health) = query.iter_mut().next() {
+            health.0 += 1;
+        }
+    });
+    c.bench_function("10k changed reactions", |b| b.iter(|| app.update()));
+}
+
+fn changed_reactions_parallel(c: &mut Criterion) {
+    let mut app = setup_with(ReactionPlugin::new().parallel(), 10_000);
+    app.add_systems(Update, |mut query: Query<&mut Health>| {
+        if let Some(mut health) = query.iter_mut().next() {
+            health.0 += 1;
+        }
+    });
+    c.bench_function("10k changed reactions (parallel)", |b| {
+        b.iter(|| app.update())
+    });
+}
+
+fn expensive_reactions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("1k expensive reactions");
+    for (name, plugin) in [
+        ("serial", ReactionPlugin::new()),
+        ("parallel", ReactionPlugin::new().parallel()),
+    ] {
+        let mut app = App::new();
+        app.add_plugins(plugin).insert_resource(Tick(0));
+        app.add_systems(Update, |mut tick: ResMut<Tick>| tick.0 += 1);
+
+        for _ in 0..1_000 {
+            app.world_mut()
+                .spawn(Reaction::new(|_: In<Scope>, tick: Res<Tick>| {
+                    let mut sum = 0i64;
+                    for i in 0..10_000 {
+                        sum = sum.wrapping_add(criterion::black_box(i * tick.0 as i64));
+                    }
+                    criterion::black_box(sum);
+                }));
+        }
+        app.update();
+
+        group.bench_function(name, |b| b.iter(|| app.update()));
+    }
+    group.finish();
+}
+
+fn derive_targets(c: &mut Criterion, name: &str, reaction: Reaction) {
+    let mut app = App::new();
+    app.add_plugins(ReactionPlugin::new());
+    app.insert_resource(Tick(0));
+
+    let mut reaction = reaction;
+    for _ in 0..1_000 {
+        reaction.add_target(app.world_mut().spawn_empty().id());
+    }
+    app.world_mut().spawn(reaction);
+    app.add_systems(Update, |mut tick: ResMut<Tick>| tick.0 += 1);
+
+    app.update();
+    c.bench_function(name, |b| b.iter(|| app.update()));
+}
+
+fn derive_reactions(c: &mut Criterion) {
+    derive_targets(
+        c,
+        "1k derived targets",
+        Reaction::derive(|_: In<Scope>, tick: Res<Tick>| Health(tick.0)),
+    );
+}
+
+fn derive_reactions_batched(c: &mut Criterion) {
+    derive_targets(
+        c,
+        "1k derived targets (batched)",
+        Reaction::derive_batched(|_: In<Scope>, tick: Res<Tick>| Health(tick.0)),
+    );
+}
+
+fn tracked_entities(c: &mut Criterion) {
+    let mut app = App::new();
+    app.add_plugins(ReactionPlugin::new());
+
+    let entities: Vec<_> = (0..10_000)
+        .map(|i| app.world_mut().spawn(Health(i)).id())
+        .collect();
+    app.world_mut().spawn(Reaction::new(
+        move |_: In<Scope>, mut query: ReactiveQuery<&Health>| {
+            for entity in &entities {
+                criterion::black_box(query.get(*entity).unwrap().0);
+            }
+        },
+    ));
+
+    app.update();
+    c.bench_function("10k tracked entities", |b| b.iter(|| app.update()));
+}
+
+fn shared_queries(c: &mut Criterion) {
+    // Every reaction shares the same matching entities.
+    let mut app = setup_reactions(ReactionPlugin::new(), 0, |reaction| reaction);
+    for _ in 0..1_000 {
+        app.world_mut().spawn(Reaction::new(
+            |_: In<Scope>, mut query: ReactiveQuery<&Health>| {
+                for health in query.iter() {
+                    criterion::black_box(health.0);
+                }
+            },
+        ));
+    }
+    app.update();
+    c.bench_function("1k identical reactive queries", |b| b.iter(|| app.update()));
+}
+
+criterion_group!(
+    benches,
+    idle_reactions,
+    idle_reactions_observed,
+    idle_reactions_ungrouped,
+    idle_reactions_grouped,
+    changed_reactions,
+    changed_reactions_parallel,
+    expensive_reactions,
+    derive_reactions,
+    derive_reactions_batched,
+    tracked_entities,
+    shared_queries
+);
+criterion_main!(benches);
@@ -0,0 +1,28 @@
+//! Run with `cargo run --example trace --features trace`.
+
+use bevy::{log::LogPlugin, prelude::*};
+use bevy_mod_reaction::{Reaction, ReactionPlugin, Scope};
+
+#[derive(Component)]
+struct Health(i32);
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, LogPlugin::default(), ReactionPlugin::new()));
+
+    let entity = app.world_mut().spawn(Health(100)).id();
+
+    // Each log from this reaction is emitted inside a `reaction{name="health"}` span.
+    app.world_mut().spawn(
+        Reaction::new(|_: In<Scope>, query: Query<&Health>| {
+            for health in &query {
+                info!("Health: {}", health.0);
+            }
+        })
+        .named("health"),
+    );
+    app.update();
+
+    app.world_mut().get_mut::<Health>(entity).unwrap().0 -= 10;
+    app.update();
+}
@@ -0,0 +1,245 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, visit_mut::VisitMut, Data, DeriveInput,
+    Fields, Index, Lifetime, Member, Type,
+};
+
+/// Implement `ReactiveSystemParam` for a struct whose fields are all reactive system parameters.
+///
+/// The struct must also derive Bevy's `SystemParam`.
+/// The struct changes when any of its fields change.
+#[proc_macro_derive(ReactiveSystemParam)]
+pub fn derive_reactive_system_param(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match reactive_system_param(input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.into_compile_error().into(),
+    }
+}
+
+fn reactive_system_param(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "ReactiveSystemParam can only be derived for structs",
+        ));
+    };
+
+    let (members, tys) = fields(&data.fields);
+
+    // Parameter states can't borrow from the world, so use `'static` field types.
+    let tys: Vec<Type> = tys
+        .into_iter()
+        .map(|ty| {
+            let mut ty = ty.clone();
+            StaticLifetimes.visit_type_mut(&mut ty);
+            ty
+        })
+        .collect();
+    let idxs: Vec<_> = (0..tys.len()).map(Index::from).collect();
+    let states: Vec<_> = (0..tys.len())
+        .map(|idx| format_ident!("state{idx}"))
+        .collect();
+
+    let krate = quote!(::bevy_mod_reaction);
+    let ecs = quote!(#krate::__private::bevy_ecs);
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Poll every field without short-circuiting, so each one keeps its own ticks up to date.
+    Ok(quote! {
+        impl #impl_generics #krate::ReactiveSystemParam for #ident #ty_generics #where_clause {
+            type State = (#(<#tys as #krate::ReactiveSystemParam>::State,)*);
+
+            fn init(
+                world: &mut #ecs::world::World,
+            ) -> <Self as #krate::ReactiveSystemParam>::State {
+                (#(<#tys as #krate::ReactiveSystemParam>::init(world),)*)
+            }
+
+            #[allow(unused_mut)]
+            fn is_changed(
+                mut world: #ecs::world::DeferredWorld,
+                state: &mut <Self as #krate::ReactiveSystemParam>::State,
+            ) -> bool {
+                let mut is_changed = false;
+                #(is_changed |= <#tys as #krate::ReactiveSystemParam>::is_changed(
+                    world.reborrow(),
+                    &mut state.#idxs,
+                );)*
+                is_changed
+            }
+
+            #[allow(unused_mut)]
+            fn is_changed_with_entity(
+                mut world: #ecs::world::DeferredWorld,
+                state: &mut <Self as #krate::ReactiveSystemParam>::State,
+                entity: #ecs::entity::Entity,
+            ) -> bool {
+                let mut is_changed = false;
+                #(is_changed |= <#tys as #krate::ReactiveSystemParam>::is_changed_with_entity(
+                    world.reborrow(),
+                    &mut state.#idxs,
+                    entity,
+                );)*
+                is_changed
+            }
+
+            #[allow(unused_mut)]
+            fn after_run(
+                mut world: #ecs::world::DeferredWorld,
+                state: &mut <Self as #krate::ReactiveSystemParam>::State,
+            ) {
+                #(<#tys as #krate::ReactiveSystemParam>::after_run(
+                    world.reborrow(),
+                    &mut state.#idxs,
+                );)*
+            }
+
+            fn init_access(
+                world: &mut #ecs::world::World,
+                access: &mut #ecs::query::FilteredAccessSet<#ecs::component::ComponentId>,
+            ) {
+                #(<#tys as #krate::ReactiveSystemParam>::init_access(world, access);)*
+            }
+
+            unsafe fn get<'__w: '__s, '__s>(
+                world: #ecs::world::unsafe_world_cell::UnsafeWorldCell<'__w>,
+                state: &'__s mut <Self as #krate::ReactiveSystemParam>::State,
+            ) -> <Self as #ecs::system::SystemParam>::Item<'__w, '__s> {
+                let (#(#states,)*) = state;
+                #ident {
+                    // SAFETY: Field access is checked for conflicts in `init_access`,
+                    // so each field can get its item from the same `world`.
+                    #(#members: unsafe {
+                        <#tys as #krate::ReactiveSystemParam>::get(world, #states)
+                    },)*
+                }
+            }
+        }
+    })
+}
+
+/// Implement `ReactiveQueryData` for a struct whose fields are all reactive query data.
+///
+/// The struct must also derive Bevy's `QueryData`.
+/// The struct changes when any of its fields change.
+#[proc_macro_derive(ReactiveQueryData)]
+pub fn derive_reactive_query_data(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match reactive_query_data(input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.into_compile_error().into(),
+    }
+}
+
+fn reactive_query_data(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "ReactiveQueryData can only be derived for structs",
+        ));
+    };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "ReactiveQueryData can't be derived for generic structs",
+        ));
+    }
+
+    let (_, tys) = fields(&data.fields);
+    let idxs: Vec<_> = (0..tys.len()).map(Index::from).collect();
+
+    let krate = quote!(::bevy_mod_reaction);
+    let ecs = quote!(#krate::__private::bevy_ecs);
+    let ident = &input.ident;
+
+    // Poll every field without short-circuiting, like `ReactiveSystemParam` above.
+    Ok(quote! {
+        impl<__F> #krate::ReactiveQueryData<__F> for #ident
+        where
+            __F: #ecs::query::QueryFilter + 'static,
+        {
+            type State = (
+                (#(<#tys as #krate::ReactiveQueryData<__F>>::State,)*),
+                #ecs::system::SystemState<#ecs::system::Query<'static, 'static, Self, __F>>,
+            );
+
+            fn init(
+                world: &mut #ecs::world::World,
+            ) -> <Self as #krate::ReactiveQueryData<__F>>::State {
+                (
+                    (#(<#tys as #krate::ReactiveQueryData<__F>>::init(world),)*),
+                    #ecs::system::SystemState::new(world),
+                )
+            }
+
+            #[allow(unused_mut)]
+            fn is_changed(
+                mut world: #ecs::world::DeferredWorld,
+                state: &mut <Self as #krate::ReactiveQueryData<__F>>::State,
+            ) -> bool {
+                let mut is_changed = false;
+                #(is_changed |= <#tys as #krate::ReactiveQueryData<__F>>::is_changed(
+                    world.reborrow(),
+                    &mut state.0.#idxs,
+                );)*
+                is_changed
+            }
+
+            #[allow(unused_mut)]
+            fn is_changed_with_entity(
+                mut world: #ecs::world::DeferredWorld,
+                state: &mut <Self as #krate::ReactiveQueryData<__F>>::State,
+                entity: #ecs::entity::Entity,
+            ) -> bool {
+                let mut is_changed = false;
+                #(is_changed |= <#tys as #krate::ReactiveQueryData<__F>>::is_changed_with_entity(
+                    world.reborrow(),
+                    &mut state.0.#idxs,
+                    entity,
+                );)*
+                is_changed
+            }
+
+            unsafe fn get<'__w, '__s>(
+                world: #ecs::world::unsafe_world_cell::UnsafeWorldCell<'__w>,
+                state: &'__s mut <Self as #krate::ReactiveQueryData<__F>>::State,
+            ) -> #ecs::system::Query<'__w, '__s, Self, __F> {
+                state.1.update_archetypes_unsafe_world_cell(world);
+
+                // SAFETY: The caller ensures `world` has access to the query's data.
+                unsafe { state.1.get_unchecked_manual(world) }
+            }
+        }
+    })
+}
+
+/// Returns the members and types of each field in `fields`.
+fn fields(fields: &Fields) -> (Vec<Member>, Vec<&Type>) {
+    match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| (Member::Named(field.ident.clone().unwrap()), &field.ty))
+            .unzip(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| (Member::Unnamed(Index::from(idx)), &field.ty))
+            .unzip(),
+        Fields::Unit => (Vec::new(), Vec::new()),
+    }
+}
+
+/// Replaces every lifetime with `'static`.
+struct StaticLifetimes;
+
+impl VisitMut for StaticLifetimes {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        *lifetime = parse_quote!('static);
+    }
+}
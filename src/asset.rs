@@ -0,0 +1,224 @@
+use crate::{system_param::add_access, ReactiveSystemParam};
+use bevy_asset::{Asset, AssetEvent, AssetId};
+use bevy_ecs::{
+    component::{ComponentId, Tick},
+    event::ManualEventReader,
+    prelude::*,
+    query::{FilteredAccess, FilteredAccessSet},
+    system::{SystemMeta, SystemParam, SystemState},
+    world::{unsafe_world_cell::UnsafeWorldCell, DeferredWorld},
+};
+
+/// State of a [`ReactiveAssetEvents`] parameter.
+pub struct ReactiveAssetEventsState<A: Asset> {
+    reader: ManualEventReader<AssetEvent<A>>,
+    changed: Vec<AssetId<A>>,
+    last_run: Option<Tick>,
+}
+
+impl<A: Asset> ReactiveAssetEventsState<A> {
+    /// Read the new [`AssetEvent`]s, at most once per frame.
+    fn update(&mut self, world: &World) {
+        let last_run = world.last_change_tick();
+        if self.last_run == Some(last_run) {
+            return;
+        }
+        self.last_run = Some(last_run);
+
+        self.read(world.get_resource::<Events<AssetEvent<A>>>());
+    }
+
+    /// Replace the changed assets with the ones from the unread `events`.
+    fn read(&mut self, events: Option<&Events<AssetEvent<A>>>) {
+        self.changed.clear();
+        if let Some(events) = events {
+            self.changed
+                .extend(self.reader.read(events).filter_map(|event| match event {
+                    AssetEvent::Added { id } | AssetEvent::Modified { id } => Some(*id),
+                    _ => None,
+                }));
+        }
+    }
+}
+
+/// Reactive system parameter for the assets of type `A` that were added or modified.
+///
+/// Reactions with this parameter run when an [`AssetEvent::Added`] or
+/// [`AssetEvent::Modified`] event is sent.
+pub struct ReactiveAssetEvents<'s, A: Asset> {
+    changed: &'s [AssetId<A>],
+}
+
+impl<A: Asset> ReactiveAssetEvents<'_, A> {
+    /// Returns `true` if the asset `id` was added or modified.
+    pub fn contains(&self, id: impl Into<AssetId<A>>) -> bool {
+        self.changed.contains(&id.into())
+    }
+
+    /// Iterate over the ids of the added or modified assets.
+    pub fn iter(&self) -> impl Iterator<Item = AssetId<A>> + '_ {
+        self.changed.iter().copied()
+    }
+
+    /// Returns the number of added or modified assets.
+    pub fn len(&self) -> usize {
+        self.changed.len()
+    }
+
+    /// Returns `true` if no assets were added or modified.
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty()
+    }
+}
+
+/// Outside of reactions, [`ReactiveAssetEvents`] works like an [`EventReader`]
+/// of the added or modified assets.
+unsafe impl<A: Asset> SystemParam for ReactiveAssetEvents<'_, A> {
+    type State = (
+        <Option<Res<'static, Events<AssetEvent<A>>>> as SystemParam>::State,
+        ReactiveAssetEventsState<A>,
+    );
+
+    type Item<'world, 'state> = ReactiveAssetEvents<'state, A>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        (
+            Option::<Res<Events<AssetEvent<A>>>>::init_state(world, system_meta),
+            ReactiveAssetEvents::<A>::init(world),
+        )
+    }
+
+    unsafe fn get_param<'world, 'state>(
+        state: &'state mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'world>,
+        change_tick: Tick,
+    ) -> Self::Item<'world, 'state> {
+        let (events, state) = state;
+
+        // SAFETY: The caller ensures `world` has read access to the events.
+        let events = unsafe {
+            Option::<Res<Events<AssetEvent<A>>>>::get_param(events, system_meta, world, change_tick)
+        };
+        state.read(events.as_deref());
+
+        ReactiveAssetEvents {
+            changed: &state.changed,
+        }
+    }
+}
+
+impl<A: Asset> ReactiveSystemParam for ReactiveAssetEvents<'_, A> {
+    type State = ReactiveAssetEventsState<A>;
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        let _ = world;
+
+        ReactiveAssetEventsState {
+            reader: ManualEventReader::default(),
+            changed: Vec::new(),
+            last_run: None,
+        }
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) -> bool {
+        state.update(&world);
+        !state.changed.is_empty()
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        // Make sure the events have a `ComponentId`, even if they aren't inserted yet.
+        SystemState::<Res<Events<AssetEvent<A>>>>::new(world);
+        let id = world
+            .components()
+            .resource_id::<Events<AssetEvent<A>>>()
+            .unwrap();
+
+        let mut filtered = FilteredAccess::default();
+        filtered.add_read(id);
+        add_access::<Self>(world, access, filtered);
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        let _ = world;
+
+        ReactiveAssetEvents {
+            changed: &state.changed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::ReactionTestApp, Reaction, Scope};
+    use bevy_asset::{AssetPlugin, Assets, LoadedFolder};
+
+    #[derive(Default, Resource)]
+    struct Seen(Vec<AssetId<LoadedFolder>>);
+
+    fn app() -> ReactionTestApp {
+        let mut app = ReactionTestApp::new();
+        app.app_mut().add_plugins(AssetPlugin::default());
+        app.world_mut().init_resource::<Seen>();
+        app
+    }
+
+    #[test]
+    fn asset_events_run_reactions() {
+        let mut app = app();
+        let reaction = app.spawn(Reaction::new(
+            |_: In<Scope>, events: ReactiveAssetEvents<LoadedFolder>, mut commands: Commands| {
+                let ids: Vec<_> = events.iter().collect();
+                commands.add(move |world: &mut World| world.resource_mut::<Seen>().0 = ids);
+            },
+        ));
+        app.step();
+        let runs = app.runs(reaction);
+
+        app.step_n(2);
+        assert_eq!(app.runs(reaction), runs);
+
+        let handle = app
+            .world_mut()
+            .resource_mut::<Assets<LoadedFolder>>()
+            .add(LoadedFolder {
+                handles: Vec::new(),
+            });
+        app.step_n(2);
+        assert_eq!(app.runs(reaction), runs + 1);
+        assert_eq!(app.world().resource::<Seen>().0, vec![handle.id()]);
+
+        app.world_mut()
+            .resource_mut::<Assets<LoadedFolder>>()
+            .get_mut(&handle)
+            .unwrap()
+            .handles
+            .clear();
+        app.step_n(2);
+        assert_eq!(app.runs(reaction), runs + 2);
+    }
+
+    #[test]
+    fn asset_events_in_system() {
+        let mut app = app();
+        let handle = app
+            .world_mut()
+            .resource_mut::<Assets<LoadedFolder>>()
+            .add(LoadedFolder {
+                handles: Vec::new(),
+            });
+        app.step();
+
+        let world = app.world_mut();
+        let mut system = IntoSystem::into_system(|events: ReactiveAssetEvents<LoadedFolder>| {
+            events.iter().collect::<Vec<_>>()
+        });
+        system.initialize(world);
+        assert_eq!(system.run((), world), vec![handle.id()]);
+        assert!(system.run((), world).is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{
+    system_param::{add_access, get_state},
+    ReactiveSystemParam,
+};
+use bevy_ecs::{
+    archetype::Archetype,
+    component::{ComponentId, Tick},
+    prelude::*,
+    query::{FilteredAccessSet, QueryFilter, QueryState, ReadOnlyQueryData},
+    system::{SystemMeta, SystemParam, SystemState},
+    world::{unsafe_world_cell::UnsafeWorldCell, DeferredWorld},
+};
+use std::ops::Deref;
+
+/// Tuple of components that can be tracked by [`ChangedAny`].
+pub trait ComponentTuple: Send + Sync + 'static {
+    /// Query data with a reference to each component.
+    type Refs: ReadOnlyQueryData + 'static;
+
+    /// Filter for entities where any of the components changed.
+    type Changed: QueryFilter + 'static;
+}
+
+macro_rules! impl_component_tuple {
+    ($($t:ident),*) => {
+        impl<$($t: Component),*> ComponentTuple for ($($t,)*) {
+            type Refs = ($(&'static $t,)*);
+
+            type Changed = Or<($(Changed<$t>,)*)>;
+        }
+    };
+}
+
+impl_component_tuple!(A);
+impl_component_tuple!(A, B);
+impl_component_tuple!(A, B, C);
+impl_component_tuple!(A, B, C, D);
+impl_component_tuple!(A, B, C, D, E);
+impl_component_tuple!(A, B, C, D, E, F);
+
+pub struct ChangedAnyState<T: ComponentTuple, F: QueryFilter + 'static> {
+    changed: QueryState<T::Refs, (T::Changed, F)>,
+    last_run: Tick,
+    query: SystemState<Query<'static, 'static, T::Refs, F>>,
+}
+
+/// Reactive query for a tuple of components that changes when any of them change.
+///
+/// Unlike a tuple of separate queries, change detection is done with a single
+/// `Or<(Changed<A>, Changed<B>, ...)>` filter.
+pub struct ChangedAny<'w, 's, T: ComponentTuple, F: QueryFilter + 'static = ()> {
+    query: Query<'w, 's, T::Refs, F>,
+}
+
+impl<'w, 's, T: ComponentTuple, F: QueryFilter + 'static> Deref for ChangedAny<'w, 's, T, F> {
+    type Target = Query<'w, 's, T::Refs, F>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.query
+    }
+}
+
+/// Outside of reactions, [`ChangedAny`] is the same as a [`Query`] for each component.
+unsafe impl<T: ComponentTuple, F: QueryFilter + 'static> SystemParam for ChangedAny<'_, '_, T, F> {
+    type State = QueryState<T::Refs, F>;
+
+    type Item<'world, 'state> = ChangedAny<'world, 'state, T, F>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        Query::<T::Refs, F>::init_state(world, system_meta)
+    }
+
+    unsafe fn new_archetype(
+        state: &mut Self::State,
+        archetype: &Archetype,
+        system_meta: &mut SystemMeta,
+    ) {
+        // SAFETY: The caller ensures `archetype` is from the world used to initialize `state`.
+        unsafe { Query::<T::Refs, F>::new_archetype(state, archetype, system_meta) }
+    }
+
+    unsafe fn get_param<'world, 'state>(
+        state: &'state mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'world>,
+        change_tick: Tick,
+    ) -> Self::Item<'world, 'state> {
+        ChangedAny {
+            // SAFETY: The caller ensures `world` has read access to each component.
+            query: unsafe { Query::get_param(state, system_meta, world, change_tick) },
+        }
+    }
+}
+
+impl<T: ComponentTuple, F: QueryFilter + 'static> ReactiveSystemParam for ChangedAny<'_, '_, T, F> {
+    type State = ChangedAnyState<T, F>;
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        ChangedAnyState {
+            changed: QueryState::new(world),
+            // Treat everything as changed on the first poll, like a new system.
+            last_run: Tick::new(world.change_tick().get().wrapping_sub(Tick::MAX.get())),
+            query: SystemState::new(world),
+        }
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) -> bool {
+        let this_run = world.increment_change_tick();
+        state.changed.update_archetypes(&world);
+
+        let is_changed = !state.changed.is_empty(&world, state.last_run, this_run);
+        state.last_run = this_run;
+        is_changed
+    }
+
+    fn is_changed_with_entity(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveSystemParam>::State,
+        entity: Entity,
+    ) -> bool {
+        state.changed.update_archetypes(&world);
+        state.changed.get_manual(&world, entity).is_ok()
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        let query = QueryState::<T::Refs, F>::new(world);
+        add_access::<Self>(world, access, query.component_access().clone());
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        ChangedAny {
+            // SAFETY: The caller ensures `world` has access to the query's data.
+            query: unsafe { get_state(&mut state.query, world) },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::ReactionTestApp, Reaction, Scope};
+
+    #[derive(Component)]
+    struct Health(i32);
+
+    #[derive(Component)]
+    struct Mana(i32);
+
+    #[derive(Component)]
+    struct Stamina(i32);
+
+    #[test]
+    fn runs_when_any_component_changes() {
+        let mut app = ReactionTestApp::new();
+        let entity = app.spawn_target((Health(1), Mana(1), Stamina(1)));
+        let reaction = app.spawn(Reaction::new(
+            |_: In<Scope>, _: ChangedAny<(Health, Mana)>| {},
+        ));
+        app.step();
+        let runs = app.runs(reaction);
+
+        app.world_mut().get_mut::<Stamina>(entity).unwrap().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), runs);
+
+        app.world_mut().get_mut::<Health>(entity).unwrap().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), runs + 1);
+
+        app.world_mut().get_mut::<Mana>(entity).unwrap().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), runs + 2);
+
+        // Both in one frame still only run the reaction once.
+        app.world_mut().get_mut::<Health>(entity).unwrap().0 += 1;
+        app.world_mut().get_mut::<Mana>(entity).unwrap().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), runs + 3);
+    }
+
+    #[test]
+    fn changed_any_in_system() {
+        let mut world = World::new();
+        world.spawn((Health(1), Mana(2)));
+        world.spawn(Health(3));
+
+        let mut system = IntoSystem::into_system(|query: ChangedAny<(Health, Mana)>| {
+            query
+                .iter()
+                .map(|(health, mana)| health.0 + mana.0)
+                .collect::<Vec<_>>()
+        });
+        system.initialize(&mut world);
+        assert_eq!(system.run((), &mut world), vec![3]);
+    }
+}
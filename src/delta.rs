@@ -0,0 +1,338 @@
+use crate::{
+    system_param::{add_access, get_state},
+    ReactiveSystemParam,
+};
+use bevy_ecs::{
+    archetype::Archetype,
+    component::{ComponentId, Tick},
+    entity::EntityHashMap,
+    prelude::*,
+    query::{FilteredAccessSet, QueryState},
+    system::{SystemMeta, SystemParam, SystemState},
+    world::{unsafe_world_cell::UnsafeWorldCell, DeferredWorld},
+};
+use std::ops::{Add, Sub};
+
+/// Numeric component that can be tracked by [`AccumulatedDelta`].
+pub trait Accumulate: Component {
+    type Value: Copy
+        + Default
+        + Add<Output = Self::Value>
+        + Sub<Output = Self::Value>
+        + Send
+        + Sync
+        + 'static;
+
+    fn value(&self) -> Self::Value;
+}
+
+pub struct AccumulatedDeltaState<T: Accumulate> {
+    changed: SystemState<Query<'static, 'static, (), Changed<T>>>,
+    query: SystemState<Query<'static, 'static, (Entity, &'static T), Changed<T>>>,
+    removed: SystemState<RemovedComponents<'static, 'static, T>>,
+    pending_removed: Vec<Entity>,
+    values: EntityHashMap<(T::Value, T::Value)>,
+}
+
+impl<T: Accumulate> AccumulatedDeltaState<T> {
+    /// Read the entities that lost `T` (or were despawned) since the last read.
+    ///
+    /// Returns `true` if any of them were tracked.
+    fn read_removed(&mut self, world: UnsafeWorldCell) -> bool {
+        let Self {
+            removed,
+            pending_removed,
+            values,
+            ..
+        } = self;
+        let start = pending_removed.len();
+        pending_removed.extend(
+            // SAFETY: Removed components are only read from the world's metadata.
+            unsafe { get_state(removed, world) }
+                .read()
+                .filter(|entity| values.contains_key(entity)),
+        );
+        pending_removed.len() > start
+    }
+}
+
+/// Reactive system parameter that accumulates the changes of a numeric component.
+///
+/// Each entity's total starts at zero the first time it's seen by the reaction,
+/// and adds the difference between the previous and current value whenever it changes.
+/// Entities that lose `T`, or are despawned, stop being tracked.
+pub struct AccumulatedDelta<'s, T: Accumulate> {
+    values: &'s EntityHashMap<(T::Value, T::Value)>,
+}
+
+impl<T: Accumulate> AccumulatedDelta<'_, T> {
+    /// Returns the accumulated delta of `entity`.
+    pub fn get(&self, entity: Entity) -> Option<T::Value> {
+        self.values.get(&entity).map(|(_, total)| *total)
+    }
+
+    /// Iterate over the accumulated delta of each entity.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, T::Value)> + '_ {
+        self.values
+            .iter()
+            .map(|(entity, (_, total))| (*entity, *total))
+    }
+}
+
+/// Outside of reactions, [`AccumulatedDelta`] accumulates the changes
+/// since the last time the system ran.
+unsafe impl<T: Accumulate> SystemParam for AccumulatedDelta<'_, T> {
+    type State = (
+        QueryState<(Entity, &'static T), Changed<T>>,
+        <RemovedComponents<'static, 'static, T> as SystemParam>::State,
+        EntityHashMap<(T::Value, T::Value)>,
+    );
+
+    type Item<'world, 'state> = AccumulatedDelta<'state, T>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        (
+            Query::<(Entity, &T), Changed<T>>::init_state(world, system_meta),
+            RemovedComponents::<T>::init_state(world, system_meta),
+            EntityHashMap::default(),
+        )
+    }
+
+    unsafe fn new_archetype(
+        state: &mut Self::State,
+        archetype: &Archetype,
+        system_meta: &mut SystemMeta,
+    ) {
+        // SAFETY: The caller ensures `archetype` is from the world used to initialize `state`.
+        unsafe {
+            Query::<(Entity, &T), Changed<T>>::new_archetype(&mut state.0, archetype, system_meta)
+        }
+    }
+
+    unsafe fn get_param<'world, 'state>(
+        state: &'state mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'world>,
+        change_tick: Tick,
+    ) -> Self::Item<'world, 'state> {
+        let (query, removed, values) = state;
+
+        // SAFETY: The caller ensures `world` has read access to `T`.
+        let (query, mut removed) = unsafe {
+            (
+                Query::get_param(query, system_meta, world, change_tick),
+                RemovedComponents::get_param(removed, system_meta, world, change_tick),
+            )
+        };
+        accumulate(values, removed.read(), query.iter());
+
+        AccumulatedDelta { values }
+    }
+}
+
+impl<T: Accumulate> ReactiveSystemParam for AccumulatedDelta<'_, T> {
+    type State = AccumulatedDeltaState<T>;
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        AccumulatedDeltaState {
+            changed: SystemState::new(world),
+            query: SystemState::new(world),
+            removed: SystemState::new(world),
+            pending_removed: Vec::new(),
+            values: EntityHashMap::default(),
+        }
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) -> bool {
+        // Removals are only kept for a couple of frames, so read them on every poll.
+        let is_removed = state.read_removed(world.as_unsafe_world_cell_readonly());
+        !state.changed.get(&world).is_empty() | is_removed
+    }
+
+    fn is_changed_with_entity(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveSystemParam>::State,
+        entity: Entity,
+    ) -> bool {
+        if state.read_removed(world.as_unsafe_world_cell_readonly())
+            && state.pending_removed.contains(&entity)
+        {
+            return true;
+        }
+
+        // Read the ticks of the target directly, so one target doesn't hide changes to another.
+        match world
+            .get_entity(entity)
+            .and_then(|entity| entity.get_ref::<T>())
+        {
+            Some(value) => value.is_changed() || !state.values.contains_key(&entity),
+            // A target without `T` can depend on any entity.
+            None => Self::is_changed(world, state),
+        }
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        let query = QueryState::<&T>::new(world);
+        add_access::<Self>(world, access, query.component_access().clone());
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        state.read_removed(world);
+        let AccumulatedDeltaState {
+            query,
+            pending_removed,
+            values,
+            ..
+        } = state;
+        // SAFETY: The caller ensures `world` has read access to `T`.
+        let query = unsafe { get_state(query, world) };
+        accumulate(values, pending_removed.drain(..), query.iter());
+
+        AccumulatedDelta { values }
+    }
+}
+
+/// Stop tracking the `removed` entities, then add the difference of each `changed` value.
+fn accumulate<'a, T: Accumulate>(
+    values: &mut EntityHashMap<(T::Value, T::Value)>,
+    removed: impl IntoIterator<Item = Entity>,
+    changed: impl IntoIterator<Item = (Entity, &'a T)>,
+) {
+    for entity in removed {
+        values.remove(&entity);
+    }
+
+    for (entity, component) in changed {
+        let value = component.value();
+        let (last, total) = values.entry(entity).or_insert((value, T::Value::default()));
+        *total = *total + (value - *last);
+        *last = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::ReactionTestApp, Reaction, Scope};
+
+    #[derive(Component)]
+    struct Score(i32);
+
+    impl Accumulate for Score {
+        type Value = i32;
+
+        fn value(&self) -> i32 {
+            self.0
+        }
+    }
+
+    #[derive(Default, Resource)]
+    struct Totals(Vec<(Entity, i32)>);
+
+    fn record(_: In<Scope>, delta: AccumulatedDelta<Score>, mut commands: Commands) {
+        let mut totals: Vec<_> = delta.iter().collect();
+        totals.sort();
+        commands.insert_resource(Totals(totals));
+    }
+
+    #[test]
+    fn accumulates_incremental_changes() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Totals>();
+        let a = app.spawn_target(Score(10));
+        let b = app.spawn_target(Score(0));
+        let reaction = app.spawn(Reaction::new(record));
+        app.step();
+        assert_eq!(app.world().resource::<Totals>().0, vec![(a, 0), (b, 0)]);
+
+        app.world_mut().get_mut::<Score>(a).unwrap().0 = 15;
+        app.step();
+        assert_eq!(app.world().resource::<Totals>().0, vec![(a, 5), (b, 0)]);
+
+        app.world_mut().get_mut::<Score>(a).unwrap().0 = 12;
+        app.world_mut().get_mut::<Score>(b).unwrap().0 = 4;
+        app.step();
+        assert_eq!(app.world().resource::<Totals>().0, vec![(a, 2), (b, 4)]);
+        let runs = app.runs(reaction);
+
+        app.step_n(2);
+        assert_eq!(app.runs(reaction), runs);
+
+        app.world_mut().get_mut::<Score>(b).unwrap().0 = 1;
+        app.step();
+        assert_eq!(app.world().resource::<Totals>().0, vec![(a, 2), (b, 1)]);
+    }
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Total(i32);
+
+    #[test]
+    fn only_changed_targets_run() {
+        let mut app = ReactionTestApp::new();
+        let a = app.spawn_target(Score(0));
+        let b = app.spawn_target(Score(0));
+        let mut reaction = Reaction::new(
+            |scope: In<Scope>, delta: AccumulatedDelta<Score>, mut commands: Commands| {
+                let total = delta.get(scope.entity).unwrap();
+                commands.entity(scope.entity).insert(Total(total));
+            },
+        );
+        reaction.add_target(a).add_target(b);
+        let reaction = app.spawn(reaction);
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+
+        app.world_mut().get_mut::<Score>(a).unwrap().0 = 2;
+        app.world_mut().get_mut::<Score>(b).unwrap().0 = 3;
+        app.step();
+        assert_eq!(app.runs(reaction), 4);
+        assert_eq!(app.world().get::<Total>(a), Some(&Total(2)));
+        assert_eq!(app.world().get::<Total>(b), Some(&Total(3)));
+
+        app.world_mut().get_mut::<Score>(b).unwrap().0 = 5;
+        app.step();
+        assert_eq!(app.runs(reaction), 5);
+        assert_eq!(app.world().get::<Total>(b), Some(&Total(5)));
+    }
+
+    #[test]
+    fn prunes_despawned_entities() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Totals>();
+        let a = app.spawn_target(Score(0));
+        let b = app.spawn_target(Score(0));
+        let reaction = app.spawn(Reaction::new(record));
+        app.step();
+
+        app.world_mut().despawn(a);
+        app.step();
+        assert_eq!(app.world().resource::<Totals>().0, vec![(b, 0)]);
+        let runs = app.runs(reaction);
+
+        app.step_n(3);
+        assert_eq!(app.runs(reaction), runs);
+    }
+
+    #[test]
+    fn accumulates_in_system() {
+        let mut world = World::new();
+        let entity = world.spawn(Score(1)).id();
+
+        let mut system =
+            IntoSystem::into_system(move |delta: AccumulatedDelta<Score>| delta.get(entity));
+        system.initialize(&mut world);
+        assert_eq!(system.run((), &mut world), Some(0));
+
+        world.get_mut::<Score>(entity).unwrap().0 = 4;
+        assert_eq!(system.run((), &mut world), Some(3));
+
+        world.get_mut::<Score>(entity).unwrap().0 = 2;
+        assert_eq!(system.run((), &mut world), Some(1));
+
+        world.despawn(entity);
+        assert_eq!(system.run((), &mut world), None);
+    }
+}
@@ -0,0 +1,128 @@
+#[cfg(feature = "diagnostics")]
+use bevy_diagnostic::{DiagnosticPath, Diagnostics};
+use bevy_ecs::{prelude::*, world::DeferredWorld};
+use std::ops::AddAssign;
+
+/// Counts of reaction activity for the current frame.
+///
+/// With the `diagnostics` feature, these are also measured in the
+/// [`DiagnosticsStore`](bevy_diagnostic::DiagnosticsStore) at the paths in the associated constants.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Resource)]
+pub struct ReactionDiagnostics {
+    /// Number of reactions that were checked for changes.
+    pub polled: usize,
+
+    /// Number of reactions that had changed.
+    pub changed: usize,
+
+    /// Number of times a reaction's system was run, including once for each target.
+    pub ran: usize,
+}
+
+#[cfg(feature = "diagnostics")]
+impl ReactionDiagnostics {
+    pub const POLLED: DiagnosticPath = DiagnosticPath::const_new("reactions/polled");
+
+    pub const CHANGED: DiagnosticPath = DiagnosticPath::const_new("reactions/changed");
+
+    pub const RAN: DiagnosticPath = DiagnosticPath::const_new("reactions/ran");
+}
+
+impl AddAssign for ReactionDiagnostics {
+    fn add_assign(&mut self, rhs: Self) {
+        self.polled += rhs.polled;
+        self.changed += rhs.changed;
+        self.ran += rhs.ran;
+    }
+}
+
+pub(crate) fn reset_diagnostics(mut reaction_diagnostics: ResMut<ReactionDiagnostics>) {
+    *reaction_diagnostics = ReactionDiagnostics::default();
+}
+
+#[cfg(feature = "diagnostics")]
+pub(crate) fn measure_diagnostics(
+    reaction_diagnostics: Res<ReactionDiagnostics>,
+    mut diagnostics: Diagnostics,
+) {
+    diagnostics.add_measurement(&ReactionDiagnostics::POLLED, || {
+        reaction_diagnostics.polled as f64
+    });
+    diagnostics.add_measurement(&ReactionDiagnostics::CHANGED, || {
+        reaction_diagnostics.changed as f64
+    });
+    diagnostics.add_measurement(&ReactionDiagnostics::RAN, || {
+        reaction_diagnostics.ran as f64
+    });
+}
+
+/// Add `diagnostics` to the [`ReactionDiagnostics`] of `world`, if it exists.
+pub(crate) fn record(world: &mut DeferredWorld, diagnostics: ReactionDiagnostics) {
+    if let Some(mut reaction_diagnostics) = world.get_resource_mut::<ReactionDiagnostics>() {
+        *reaction_diagnostics += diagnostics;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::ReactionTestApp, Reaction, Scope};
+    #[cfg(feature = "diagnostics")]
+    use bevy_diagnostic::DiagnosticsStore;
+
+    #[derive(Default, Resource)]
+    struct Trigger(u32);
+
+    #[derive(Default, Resource)]
+    struct Other;
+
+    #[test]
+    fn counts_reaction_activity() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        app.world_mut().init_resource::<Other>();
+        let a = app.spawn_target(());
+        let b = app.spawn_target(());
+        let mut reaction = Reaction::new(|_: In<Scope>, _: Res<Trigger>| {});
+        reaction.add_target(a).add_target(b);
+        app.spawn(reaction);
+        app.spawn(Reaction::new(|_: In<Scope>, _: Res<Other>| {}));
+        app.step_n(2);
+        assert_eq!(
+            *app.world().resource::<ReactionDiagnostics>(),
+            ReactionDiagnostics {
+                polled: 2,
+                changed: 0,
+                ran: 0,
+            }
+        );
+
+        app.world_mut().resource_mut::<Trigger>().0 += 1;
+        app.step();
+        assert_eq!(
+            *app.world().resource::<ReactionDiagnostics>(),
+            ReactionDiagnostics {
+                polled: 2,
+                changed: 1,
+                ran: 2,
+            }
+        );
+    }
+
+    #[cfg(feature = "diagnostics")]
+    #[test]
+    fn measures_reaction_activity() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let a = app.spawn_target(());
+        let b = app.spawn_target(());
+        let mut reaction = Reaction::new(|_: In<Scope>, _: Res<Trigger>| {});
+        reaction.add_target(a).add_target(b);
+        app.spawn(reaction);
+        app.step();
+
+        let store = app.world().resource::<DiagnosticsStore>();
+        let ran = store.get(&ReactionDiagnostics::RAN).unwrap();
+        assert_eq!(ran.value(), Some(2.));
+    }
+}
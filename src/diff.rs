@@ -0,0 +1,32 @@
+use bevy_ecs::prelude::*;
+
+/// Tuple of components that can be inserted by [`Reaction::derive_diff`](crate::Reaction::derive_diff),
+/// only inserting the components that changed.
+pub trait DiffBundle: Send + Sync + 'static {
+    /// Insert each component of this bundle into `entity`
+    /// that's different from its current value.
+    fn insert_changed(self, entity: &mut EntityWorldMut);
+}
+
+macro_rules! impl_diff_bundle {
+    ($($t:ident),*) => {
+        impl<$($t: Component + PartialEq),*> DiffBundle for ($($t,)*) {
+            #[allow(non_snake_case)]
+            fn insert_changed(self, entity: &mut EntityWorldMut) {
+                let ($($t,)*) = self;
+                $(
+                    if entity.get::<$t>() != Some(&$t) {
+                        entity.insert($t);
+                    }
+                )*
+            }
+        }
+    };
+}
+
+impl_diff_bundle!(A);
+impl_diff_bundle!(A, B);
+impl_diff_bundle!(A, B, C);
+impl_diff_bundle!(A, B, C, D);
+impl_diff_bundle!(A, B, C, D, E);
+impl_diff_bundle!(A, B, C, D, E, F);
@@ -0,0 +1,94 @@
+use bevy_ecs::prelude::*;
+use bevy_utils::tracing::error;
+use std::{error::Error, fmt, sync::Arc};
+
+/// An error returned from a reaction created with [`Reaction::try_new`](crate::Reaction::try_new).
+#[derive(Debug)]
+pub struct ReactionError {
+    /// The entity the reaction was run for.
+    pub entity: Entity,
+
+    /// The error returned by the reaction.
+    pub error: Box<dyn Error + Send + Sync>,
+}
+
+impl fmt::Display for ReactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Reaction for {} failed: {}", self.entity, self.error)
+    }
+}
+
+impl Error for ReactionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+/// Handler for errors returned from reactions.
+///
+/// Defaults to logging each error.
+#[derive(Clone, Resource)]
+pub struct ReactionErrorHandler(pub Arc<dyn Fn(ReactionError) + Send + Sync>);
+
+impl ReactionErrorHandler {
+    /// Create a new handler from a callback.
+    pub fn new(f: impl Fn(ReactionError) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
+impl Default for ReactionErrorHandler {
+    fn default() -> Self {
+        Self::new(|error| error!("{error}"))
+    }
+}
+
+/// Handle `error` with the [`ReactionErrorHandler`] in `world`, or the default handler.
+pub(crate) fn handle_error(world: &mut World, error: ReactionError) {
+    let handler = world
+        .get_resource::<ReactionErrorHandler>()
+        .cloned()
+        .unwrap_or_default();
+    (handler.0)(error);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::ReactionTestApp, Reaction, Scope};
+    use std::sync::Mutex;
+
+    #[derive(Default, Resource)]
+    struct Trigger(u32);
+
+    #[test]
+    fn errors_are_passed_to_the_handler() {
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        app.world_mut().insert_resource(ReactionErrorHandler::new({
+            let errors = errors.clone();
+            move |error| errors.lock().unwrap().push(error.to_string())
+        }));
+        let reaction =
+            app.spawn(Reaction::try_new(
+                |_: In<Scope>, trigger: Res<Trigger>| match trigger.0 {
+                    1 => Err(String::from("odd trigger")),
+                    _ => Ok(()),
+                },
+            ));
+        app.step();
+        assert!(errors.lock().unwrap().is_empty());
+
+        app.world_mut().resource_mut::<Trigger>().0 = 1;
+        app.step();
+        assert_eq!(
+            *errors.lock().unwrap(),
+            [format!("Reaction for {reaction} failed: odd trigger")]
+        );
+
+        app.world_mut().resource_mut::<Trigger>().0 = 2;
+        app.step();
+        assert_eq!(errors.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{
+    system_param::{add_access, get_state},
+    ReactiveSystemParam,
+};
+use bevy_ecs::{
+    archetype::Archetype,
+    component::{ComponentId, Tick},
+    prelude::*,
+    query::{FilteredAccessSet, QueryFilter, QueryState, ROQueryItem, ReadOnlyQueryData},
+    system::{SystemMeta, SystemParam, SystemState},
+    world::{unsafe_world_cell::UnsafeWorldCell, DeferredWorld},
+};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+pub struct HashedState<D: ReadOnlyQueryData + 'static, F: QueryFilter + 'static> {
+    query: SystemState<Query<'static, 'static, D, F>>,
+    hash: Option<u64>,
+    /// Change tick of the last poll, and whether the hash changed in it.
+    polled: Option<(Tick, bool)>,
+}
+
+/// Reactive query that only changes when the hash of all its items changes.
+///
+/// Unlike [`Query`], this catches values that were changed and then set back to what they were.
+/// The items are hashed once per change tick, so every target of a reaction sees the same change,
+/// and the hash doesn't depend on the order of the items.
+pub struct Hashed<'w, 's, D: ReadOnlyQueryData + 'static, F: QueryFilter + 'static = ()> {
+    query: Query<'w, 's, D, F>,
+}
+
+impl<'w, 's, D: ReadOnlyQueryData + 'static, F: QueryFilter + 'static> Deref
+    for Hashed<'w, 's, D, F>
+{
+    type Target = Query<'w, 's, D, F>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.query
+    }
+}
+
+/// Outside of reactions, [`Hashed`] is the same as a [`Query`].
+unsafe impl<D: ReadOnlyQueryData + 'static, F: QueryFilter + 'static> SystemParam
+    for Hashed<'_, '_, D, F>
+{
+    type State = QueryState<D, F>;
+
+    type Item<'world, 'state> = Hashed<'world, 'state, D, F>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        Query::<D, F>::init_state(world, system_meta)
+    }
+
+    unsafe fn new_archetype(
+        state: &mut Self::State,
+        archetype: &Archetype,
+        system_meta: &mut SystemMeta,
+    ) {
+        // SAFETY: The caller ensures `archetype` is from the world used to initialize `state`.
+        unsafe { Query::<D, F>::new_archetype(state, archetype, system_meta) }
+    }
+
+    unsafe fn get_param<'world, 'state>(
+        state: &'state mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'world>,
+        change_tick: Tick,
+    ) -> Self::Item<'world, 'state> {
+        Hashed {
+            // SAFETY: The caller ensures `world` has access to the query's data.
+            query: unsafe { Query::get_param(state, system_meta, world, change_tick) },
+        }
+    }
+}
+
+impl<D, F> ReactiveSystemParam for Hashed<'_, '_, D, F>
+where
+    D: ReadOnlyQueryData + 'static,
+    F: QueryFilter + 'static,
+    for<'a> ROQueryItem<'a, D>: Hash,
+{
+    type State = HashedState<D, F>;
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        HashedState {
+            query: SystemState::new(world),
+            hash: None,
+            polled: None,
+        }
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) -> bool {
+        // Targets are polled in the same pass, so hash the items once for all of them.
+        let tick = world.last_change_tick();
+        if let Some((polled_at, is_changed)) = state.polled {
+            if polled_at == tick {
+                return is_changed;
+            }
+        }
+
+        let hash = hash_query(&state.query.get(&world));
+        let is_changed = state.hash != Some(hash);
+        state.hash = Some(hash);
+        state.polled = Some((tick, is_changed));
+        is_changed
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        let query = QueryState::<D, F>::new(world);
+        add_access::<Self>(world, access, query.component_access().clone());
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        Hashed {
+            // SAFETY: The caller ensures `world` has access to the query's data.
+            query: unsafe { get_state(&mut state.query, world) },
+        }
+    }
+}
+
+fn hash_query<D, F>(query: &Query<D, F>) -> u64
+where
+    D: ReadOnlyQueryData,
+    F: QueryFilter,
+    for<'a> ROQueryItem<'a, D>: Hash,
+{
+    // Add the hash of each item, so moving entities between archetypes doesn't change the sum.
+    query
+        .iter()
+        .map(|item| {
+            let mut hasher = DefaultHasher::new();
+            item.hash(&mut hasher);
+            hasher.finish()
+        })
+        .fold(0, u64::wrapping_add)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::ReactionTestApp, Reaction, Scope};
+
+    #[derive(Component, Hash)]
+    struct Value(i32);
+
+    #[test]
+    fn only_runs_when_the_hash_changes() {
+        let mut app = ReactionTestApp::new();
+        let entity = app.spawn_target(Value(1));
+        let reaction = app.spawn(Reaction::new(|_: In<Scope>, _: Hashed<&Value>| {}));
+        app.step();
+        let runs = app.runs(reaction);
+
+        // Changed and then set back, so the hash is the same.
+        app.world_mut().get_mut::<Value>(entity).unwrap().0 = 2;
+        app.world_mut().get_mut::<Value>(entity).unwrap().0 = 1;
+        app.step();
+        assert_eq!(app.runs(reaction), runs);
+
+        app.world_mut().get_mut::<Value>(entity).unwrap().0 = 3;
+        app.step();
+        assert_eq!(app.runs(reaction), runs + 1);
+
+        app.step();
+        assert_eq!(app.runs(reaction), runs + 1);
+
+        app.spawn_target(Value(4));
+        app.step();
+        assert_eq!(app.runs(reaction), runs + 2);
+    }
+
+    #[test]
+    fn every_target_sees_the_change() {
+        let mut app = ReactionTestApp::new();
+        let a = app.spawn_target(Value(1));
+        let b = app.spawn_target(Value(2));
+        let mut reaction = Reaction::new(|_: In<Scope>, _: Hashed<&Value>| {});
+        reaction.add_target(a).add_target(b);
+        let reaction = app.spawn(reaction);
+
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+
+        app.world_mut().get_mut::<Value>(b).unwrap().0 = 3;
+        app.step();
+        assert_eq!(app.runs(reaction), 4);
+
+        app.step();
+        assert_eq!(app.runs(reaction), 4);
+    }
+
+    #[derive(Component)]
+    struct Marker;
+
+    #[test]
+    fn archetype_moves_are_not_changes() {
+        let mut app = ReactionTestApp::new();
+        let a = app.spawn_target(Value(1));
+        app.spawn_target(Value(2));
+        let reaction = app.spawn(Reaction::new(|_: In<Scope>, _: Hashed<&Value>| {}));
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+
+        // Moving `a` to a new archetype changes the order it's iterated in.
+        app.world_mut().entity_mut(a).insert(Marker);
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+    }
+
+    #[test]
+    fn hashed_in_system() {
+        let mut world = World::new();
+        world.spawn(Value(1));
+        world.spawn(Value(2));
+
+        let mut system = IntoSystem::into_system(|query: Hashed<&Value>| {
+            query.iter().map(|value| value.0).sum::<i32>()
+        });
+        system.initialize(&mut world);
+        assert_eq!(system.run((), &mut world), 3);
+    }
+}
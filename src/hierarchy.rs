@@ -0,0 +1,612 @@
+use crate::{
+    system_param::{add_access, get_state},
+    ReactiveQuery, ReactiveQueryData, ReactiveQueryState, ReactiveSystemParam,
+};
+use bevy_ecs::{
+    archetype::Archetype,
+    component::{ComponentId, Tick},
+    entity::EntityHashSet,
+    event::ManualEventReader,
+    prelude::*,
+    query::{FilteredAccessSet, QueryFilter, QueryState, ReadOnlyQueryData, WorldQuery},
+    removal_detection::RemovedComponentEntity,
+    system::{SystemMeta, SystemParam, SystemState},
+    world::{unsafe_world_cell::UnsafeWorldCell, DeferredWorld},
+};
+use bevy_hierarchy::{Children, HierarchyQueryExt, Parent};
+
+/// State of a reactive hierarchy parameter for the component `T`.
+///
+/// The hierarchy is polled once per change tick, so every target of a reaction sees its changes.
+pub struct HierarchyState<T: Component> {
+    changed: QueryState<(), Changed<T>>,
+    /// Ticks of the last poll, to check changes to `T` since the poll before it.
+    previous_run: Tick,
+    last_run: Tick,
+    removed: ManualEventReader<RemovedComponentEntity>,
+    /// Entities that lost `T` in the last poll.
+    removed_entities: EntityHashSet,
+    /// Change tick of the last poll, and whether the hierarchy changed in it.
+    polled: Option<(Tick, bool)>,
+    query: SystemState<Query<'static, 'static, &'static T>>,
+}
+
+impl<T: Component> HierarchyState<T> {
+    fn new(world: &mut World) -> Self {
+        Self {
+            changed: QueryState::new(world),
+            previous_run: Tick::new(0),
+            // Treat everything as changed on the first poll, like a new system.
+            last_run: Tick::new(world.change_tick().get().wrapping_sub(Tick::MAX.get())),
+            removed: ManualEventReader::default(),
+            removed_entities: EntityHashSet::default(),
+            polled: None,
+            query: SystemState::new(world),
+        }
+    }
+
+    fn is_changed(&mut self, world: DeferredWorld) -> bool {
+        let tick = world.last_change_tick();
+        if let Some((polled_at, is_changed)) = self.polled {
+            if polled_at == tick {
+                return is_changed;
+            }
+        }
+
+        let this_run = world.increment_change_tick();
+        self.changed.update_archetypes(&world);
+
+        let mut is_changed = !self.changed.is_empty(&world, self.last_run, this_run);
+        self.previous_run = self.last_run;
+        self.last_run = this_run;
+
+        // Hierarchy components are removed along with the last child or parent.
+        self.removed_entities.clear();
+        if let Some(events) = world
+            .components()
+            .component_id::<T>()
+            .and_then(|id| world.removed_components().get(id))
+        {
+            self.removed_entities
+                .extend(self.removed.read(events).cloned().map(Entity::from));
+            is_changed |= !self.removed_entities.is_empty();
+        }
+
+        self.polled = Some((tick, is_changed));
+        is_changed
+    }
+
+    fn is_changed_with_entity(&mut self, mut world: DeferredWorld, entity: Entity) -> bool {
+        if !self.is_changed(world.reborrow()) {
+            return false;
+        }
+
+        // Only check the target itself, so changes to other targets don't run it.
+        self.removed_entities.contains(&entity)
+            || world
+                .get_entity(entity)
+                .and_then(|entity| entity.get_change_ticks::<T>())
+                .is_some_and(|ticks| ticks.is_changed(self.previous_run, self.last_run))
+    }
+
+    fn init_access<P>(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        let query = QueryState::<&T>::new(world);
+        add_access::<P>(world, access, query.component_access().clone());
+    }
+}
+
+/// Reactive system parameter to read the [`Children`] of entities.
+///
+/// Reactions with this parameter run when children are added or removed.
+pub struct ReactiveChildren<'w, 's> {
+    query: Query<'w, 's, &'static Children>,
+}
+
+impl ReactiveChildren<'_, '_> {
+    /// Returns the children of `entity`, or an empty slice if it has none.
+    pub fn get(&self, entity: Entity) -> &[Entity] {
+        self.query
+            .get(entity)
+            .map(|children| &**children)
+            .unwrap_or_default()
+    }
+}
+
+/// Outside of reactions, [`ReactiveChildren`] works like a `Query<&Children>`.
+unsafe impl SystemParam for ReactiveChildren<'_, '_> {
+    type State = QueryState<&'static Children>;
+
+    type Item<'world, 'state> = ReactiveChildren<'world, 'state>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        Query::<&Children>::init_state(world, system_meta)
+    }
+
+    unsafe fn new_archetype(
+        state: &mut Self::State,
+        archetype: &Archetype,
+        system_meta: &mut SystemMeta,
+    ) {
+        // SAFETY: The caller ensures `archetype` is from the world used to initialize `state`.
+        unsafe { Query::<&Children>::new_archetype(state, archetype, system_meta) }
+    }
+
+    unsafe fn get_param<'world, 'state>(
+        state: &'state mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'world>,
+        change_tick: Tick,
+    ) -> Self::Item<'world, 'state> {
+        ReactiveChildren {
+            // SAFETY: The caller ensures `world` has read access to `Children`.
+            query: unsafe { Query::get_param(state, system_meta, world, change_tick) },
+        }
+    }
+}
+
+impl ReactiveSystemParam for ReactiveChildren<'_, '_> {
+    type State = HierarchyState<Children>;
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        HierarchyState::new(world)
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) -> bool {
+        state.is_changed(world)
+    }
+
+    fn is_changed_with_entity(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveSystemParam>::State,
+        entity: Entity,
+    ) -> bool {
+        state.is_changed_with_entity(world, entity)
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        HierarchyState::<Children>::init_access::<Self>(world, access);
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        ReactiveChildren {
+            // SAFETY: The caller ensures `world` has read access to `Children`.
+            query: unsafe { get_state(&mut state.query, world) },
+        }
+    }
+}
+
+/// Reactive system parameter to read the [`Parent`] of entities.
+///
+/// Reactions with this parameter run when entities are added to or removed from a parent.
+pub struct ReactiveParent<'w, 's> {
+    query: Query<'w, 's, &'static Parent>,
+}
+
+impl ReactiveParent<'_, '_> {
+    /// Returns the parent of `entity`, if it has one.
+    pub fn get(&self, entity: Entity) -> Option<Entity> {
+        self.query.get(entity).ok().map(|parent| parent.get())
+    }
+}
+
+/// Outside of reactions, [`ReactiveParent`] works like a `Query<&Parent>`.
+unsafe impl SystemParam for ReactiveParent<'_, '_> {
+    type State = QueryState<&'static Parent>;
+
+    type Item<'world, 'state> = ReactiveParent<'world, 'state>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        Query::<&Parent>::init_state(world, system_meta)
+    }
+
+    unsafe fn new_archetype(
+        state: &mut Self::State,
+        archetype: &Archetype,
+        system_meta: &mut SystemMeta,
+    ) {
+        // SAFETY: The caller ensures `archetype` is from the world used to initialize `state`.
+        unsafe { Query::<&Parent>::new_archetype(state, archetype, system_meta) }
+    }
+
+    unsafe fn get_param<'world, 'state>(
+        state: &'state mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'world>,
+        change_tick: Tick,
+    ) -> Self::Item<'world, 'state> {
+        ReactiveParent {
+            // SAFETY: The caller ensures `world` has read access to `Parent`.
+            query: unsafe { Query::get_param(state, system_meta, world, change_tick) },
+        }
+    }
+}
+
+impl ReactiveSystemParam for ReactiveParent<'_, '_> {
+    type State = HierarchyState<Parent>;
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        HierarchyState::new(world)
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) -> bool {
+        state.is_changed(world)
+    }
+
+    fn is_changed_with_entity(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveSystemParam>::State,
+        entity: Entity,
+    ) -> bool {
+        state.is_changed_with_entity(world, entity)
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        HierarchyState::<Parent>::init_access::<Self>(world, access);
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        ReactiveParent {
+            // SAFETY: The caller ensures `world` has read access to `Parent`.
+            query: unsafe { get_state(&mut state.query, world) },
+        }
+    }
+}
+
+/// State of a [`ReactiveDescendants`] parameter.
+pub struct ReactiveDescendantsState<D, F, S>
+where
+    D: ReadOnlyQueryData + 'static,
+    F: QueryFilter + 'static,
+{
+    children: HierarchyState<Children>,
+    roots: EntityHashSet,
+    query: ReactiveQueryState<D, F, S>,
+}
+
+/// Reactive query for the descendants of an entity, like a [`ReactiveQuery`] for a subtree.
+///
+/// Reactions with this parameter run when any visited descendant changes,
+/// or when the hierarchy changes. Entities that are moved out of every visited subtree
+/// stop being tracked, so re-parenting is handled.
+pub struct ReactiveDescendants<'w, 's, D, F = ()>
+where
+    D: ReadOnlyQueryData + 'static,
+    F: QueryFilter + 'static,
+{
+    children: Query<'w, 's, &'static Children>,
+    roots: &'s mut EntityHashSet,
+    query: ReactiveQuery<'w, 's, D, F>,
+}
+
+impl<D, F> ReactiveDescendants<'_, '_, D, F>
+where
+    D: ReadOnlyQueryData + 'static,
+    F: QueryFilter + 'static,
+{
+    /// Run `f` for each descendant of `root` that matches the query, tracking each of them.
+    pub fn for_each(
+        &mut self,
+        root: Entity,
+        mut f: impl FnMut(Entity, <D as WorldQuery>::Item<'_>),
+    ) {
+        self.roots.insert(root);
+        for entity in self.children.iter_descendants(root) {
+            if let Ok(item) = self.query.get(entity) {
+                f(entity, item);
+            }
+        }
+    }
+}
+
+/// Outside of reactions, [`ReactiveDescendants`] works like a `Query<&Children>`
+/// paired with a [`ReactiveQuery`].
+unsafe impl<D, F> SystemParam for ReactiveDescendants<'_, '_, D, F>
+where
+    D: ReadOnlyQueryData + 'static,
+    F: QueryFilter + 'static,
+{
+    type State = (
+        QueryState<&'static Children>,
+        EntityHashSet,
+        <ReactiveQuery<'static, 'static, D, F> as SystemParam>::State,
+    );
+
+    type Item<'world, 'state> = ReactiveDescendants<'world, 'state, D, F>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        (
+            Query::<&Children>::init_state(world, system_meta),
+            EntityHashSet::default(),
+            ReactiveQuery::<D, F>::init_state(world, system_meta),
+        )
+    }
+
+    unsafe fn new_archetype(
+        state: &mut Self::State,
+        archetype: &Archetype,
+        system_meta: &mut SystemMeta,
+    ) {
+        // SAFETY: The caller ensures `archetype` is from the world used to initialize `state`.
+        unsafe {
+            Query::<&Children>::new_archetype(&mut state.0, archetype, system_meta);
+            ReactiveQuery::<D, F>::new_archetype(&mut state.2, archetype, system_meta);
+        }
+    }
+
+    unsafe fn get_param<'world, 'state>(
+        state: &'state mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'world>,
+        change_tick: Tick,
+    ) -> Self::Item<'world, 'state> {
+        let (children, roots, query) = state;
+
+        // SAFETY: The caller ensures `world` has access to `Children` and the query's data.
+        unsafe {
+            ReactiveDescendants {
+                children: Query::get_param(children, system_meta, world, change_tick),
+                roots,
+                query: ReactiveQuery::get_param(query, system_meta, world, change_tick),
+            }
+        }
+    }
+}
+
+impl<D, F> ReactiveSystemParam for ReactiveDescendants<'_, '_, D, F>
+where
+    D: ReactiveQueryData<F> + ReadOnlyQueryData + 'static,
+    F: QueryFilter + 'static,
+{
+    type State = ReactiveDescendantsState<D, F, <D as ReactiveQueryData<F>>::State>;
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        ReactiveDescendantsState {
+            children: HierarchyState::new(world),
+            roots: EntityHashSet::default(),
+            query: ReactiveQuery::<D, F>::init(world),
+        }
+    }
+
+    fn is_changed(
+        mut world: DeferredWorld,
+        state: &mut <Self as ReactiveSystemParam>::State,
+    ) -> bool {
+        let is_hierarchy_changed = state.children.is_changed(world.reborrow());
+        if is_hierarchy_changed {
+            // Stop tracking entities that were moved out of every visited subtree.
+            state.roots.retain(|root| world.get_entity(*root).is_some());
+            let children = state.children.query.get(&world);
+            let descendants: EntityHashSet = state
+                .roots
+                .iter()
+                .flat_map(|root| children.iter_descendants(*root))
+                .collect();
+            state
+                .query
+                .retain_tracked(|entity| descendants.contains(entity));
+        }
+
+        // Always poll the query too, so it doesn't fall behind.
+        ReactiveQuery::<D, F>::is_changed(world, &mut state.query) | is_hierarchy_changed
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        HierarchyState::<Children>::init_access::<Self>(world, access);
+        ReactiveQuery::<D, F>::init_access(world, access);
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        // SAFETY: Access to `Children` and the query is checked for conflicts in `init_access`.
+        ReactiveDescendants {
+            children: unsafe { get_state(&mut state.children.query, world) },
+            roots: &mut state.roots,
+            query: unsafe {
+                <ReactiveQuery<D, F> as ReactiveSystemParam>::get(world, &mut state.query)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::ReactionTestApp, Reaction, Scope};
+    use bevy_hierarchy::{BuildWorldChildren, DespawnRecursiveExt};
+
+    #[test]
+    fn children_changes_run_reactions() {
+        let mut app = ReactionTestApp::new();
+        let parent = app.spawn_target(());
+        let reaction = app.spawn(Reaction::new(
+            move |_: In<Scope>, children: ReactiveChildren, mut commands: Commands| {
+                let count = children.get(parent).len();
+                commands.entity(parent).insert(Count(count));
+            },
+        ));
+        app.step();
+
+        let child = app.world_mut().spawn_empty().set_parent(parent).id();
+        app.step();
+        assert_eq!(app.world().get::<Count>(parent).unwrap().0, 1);
+        let runs = app.runs(reaction);
+
+        app.step();
+        assert_eq!(app.runs(reaction), runs);
+
+        app.world_mut().entity_mut(child).despawn_recursive();
+        app.step();
+        assert_eq!(app.runs(reaction), runs + 1);
+        assert!(app
+            .world()
+            .get::<Count>(parent)
+            .is_some_and(|count| count.0 == 0));
+    }
+
+    #[test]
+    fn parent_changes_run_reactions() {
+        let mut app = ReactionTestApp::new();
+        let parent = app.spawn_target(());
+        let child = app.spawn_target(());
+        let reaction = app.spawn(Reaction::new(
+            move |_: In<Scope>, parent: ReactiveParent, mut commands: Commands| {
+                let has_parent = parent.get(child).is_some();
+                commands.entity(child).insert(Count(has_parent as usize));
+            },
+        ));
+        app.step();
+        let runs = app.runs(reaction);
+
+        app.world_mut().entity_mut(child).set_parent(parent);
+        app.step();
+        assert_eq!(app.runs(reaction), runs + 1);
+        assert_eq!(app.world().get::<Count>(child).unwrap().0, 1);
+    }
+
+    #[derive(Component)]
+    struct Count(usize);
+
+    #[test]
+    fn only_changed_targets_run() {
+        let mut app = ReactionTestApp::new();
+        let a = app.spawn_target(());
+        let b = app.spawn_target(());
+        app.world_mut().spawn_empty().set_parent(a);
+        let child = app.world_mut().spawn_empty().set_parent(b).id();
+        let mut reaction = Reaction::new(
+            |scope: In<Scope>, children: ReactiveChildren, mut commands: Commands| {
+                let count = children.get(scope.entity).len();
+                commands.entity(scope.entity).insert(Count(count));
+            },
+        );
+        reaction.add_target(a).add_target(b);
+        let reaction = app.spawn(reaction);
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+
+        app.world_mut().spawn_empty().set_parent(a);
+        app.world_mut().spawn_empty().set_parent(b);
+        app.step();
+        assert_eq!(app.runs(reaction), 4);
+        assert_eq!(app.world().get::<Count>(a).unwrap().0, 2);
+        assert_eq!(app.world().get::<Count>(b).unwrap().0, 2);
+
+        app.step();
+        assert_eq!(app.runs(reaction), 4);
+
+        // Removing the last child removes `Children`, which only changes `b`.
+        app.world_mut().entity_mut(child).remove_parent();
+        app.step();
+        assert_eq!(app.runs(reaction), 5);
+        assert_eq!(app.world().get::<Count>(b).unwrap().0, 1);
+    }
+
+    #[test]
+    fn parent_changes_run_targets() {
+        let mut app = ReactionTestApp::new();
+        let parent = app.spawn_target(());
+        let a = app.world_mut().spawn_empty().set_parent(parent).id();
+        let b = app.world_mut().spawn_empty().set_parent(parent).id();
+        let mut reaction = Reaction::new(
+            |scope: In<Scope>, parent: ReactiveParent, mut commands: Commands| {
+                let has_parent = parent.get(scope.entity).is_some();
+                commands
+                    .entity(scope.entity)
+                    .insert(Count(has_parent as usize));
+            },
+        );
+        reaction.add_target(a).add_target(b);
+        let reaction = app.spawn(reaction);
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+
+        app.world_mut().entity_mut(a).remove_parent();
+        app.world_mut().entity_mut(b).remove_parent();
+        app.step();
+        assert_eq!(app.runs(reaction), 4);
+        assert_eq!(app.world().get::<Count>(a).unwrap().0, 0);
+        assert_eq!(app.world().get::<Count>(b).unwrap().0, 0);
+    }
+
+    #[test]
+    fn hierarchy_in_system() {
+        let mut world = World::new();
+        let parent = world.spawn_empty().id();
+        let child = world.spawn_empty().set_parent(parent).id();
+
+        let mut system =
+            IntoSystem::into_system(move |children: ReactiveChildren, parents: ReactiveParent| {
+                (children.get(parent).to_vec(), parents.get(child))
+            });
+        system.initialize(&mut world);
+        assert_eq!(system.run((), &mut world), (vec![child], Some(parent)));
+    }
+
+    #[derive(Component)]
+    struct Health(i32);
+
+    #[test]
+    fn descendant_changes_run_reactions() {
+        let mut app = ReactionTestApp::new();
+        let root = app.spawn_target(());
+        let child = app.world_mut().spawn(Health(1)).set_parent(root).id();
+        let grandchild = app.world_mut().spawn(Health(2)).set_parent(child).id();
+        let other = app.spawn_target(Health(3));
+        let reaction = app.spawn(Reaction::new(
+            move |_: In<Scope>,
+                  mut descendants: ReactiveDescendants<&Health>,
+                  mut commands: Commands| {
+                let mut total = 0;
+                descendants.for_each(root, |_, health| total += health.0);
+                commands.entity(root).insert(Count(total as usize));
+            },
+        ));
+        app.step();
+        assert_eq!(app.world().get::<Count>(root).unwrap().0, 3);
+        let runs = app.runs(reaction);
+
+        app.world_mut().get_mut::<Health>(other).unwrap().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), runs);
+
+        app.world_mut().get_mut::<Health>(grandchild).unwrap().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), runs + 1);
+        assert_eq!(app.world().get::<Count>(root).unwrap().0, 4);
+
+        // Moved out of the subtree, so changes no longer run the reaction.
+        app.world_mut().entity_mut(grandchild).remove_parent();
+        app.step();
+        assert_eq!(app.world().get::<Count>(root).unwrap().0, 1);
+        let runs = app.runs(reaction);
+
+        app.world_mut().get_mut::<Health>(grandchild).unwrap().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), runs);
+    }
+
+    #[test]
+    fn descendants_in_system() {
+        let mut world = World::new();
+        let root = world.spawn_empty().id();
+        let child = world.spawn(Health(1)).set_parent(root).id();
+        world.spawn(Health(2)).set_parent(child);
+
+        let mut system =
+            IntoSystem::into_system(move |mut descendants: ReactiveDescendants<&Health>| {
+                let mut total = 0;
+                descendants.for_each(root, |_, health| total += health.0);
+                total
+            });
+        system.initialize(&mut world);
+        assert_eq!(system.run((), &mut world), 3);
+    }
+}
@@ -1,8 +1,28 @@
 use crate::{
-    FunctionReactiveSystem, ReactiveSystem, ReactiveSystemParam, ReactiveSystemParamFunction,
+    system_param::{add_deferred_access, write_all_access},
+    BoxedReactiveSystem, FunctionReactiveSystem, ReactiveSystem, ReactiveSystemParam,
+    ReactiveSystemParamFunction,
+};
+use bevy_ecs::{
+    component::{ComponentId, Tick},
+    entity::{EntityHashMap, EntityHashSet},
+    event::ManualEventReader,
+    prelude::*,
+    query::{FilteredAccessSet, QueryState},
+    removal_detection::RemovedComponentEntity,
+    world::Command,
+    world::DeferredWorld,
+};
+use bevy_tasks::{IoTaskPool, TaskPool};
+#[cfg(feature = "time")]
+use bevy_time::Time;
+#[cfg(feature = "time")]
+use std::time::Duration;
+use std::{
+    future::Future,
+    marker::PhantomData,
+    sync::{Arc, Mutex, PoisonError},
 };
-use bevy_ecs::{prelude::*, world::DeferredWorld};
-use std::marker::PhantomData;
 
 pub trait IntoReactiveSystem<Marker> {
     type System: ReactiveSystem;
@@ -21,6 +41,201 @@ pub trait IntoReactiveSystem<Marker> {
             b: system.into_reactive_system(),
         }
     }
+
+    /// Pair the output of this system with the entity it ran for.
+    ///
+    /// This lets later stages of a pipeline with many targets know which target produced a value.
+    fn with_entity(self) -> WithEntity<Self::System>
+    where
+        Self: Sized,
+    {
+        WithEntity {
+            system: self.into_reactive_system(),
+        }
+    }
+
+    /// Pipe the output of this system into `system`, only if it's `Some`,
+    /// and wrap the output of `system` in `Some`.
+    ///
+    /// When the output is `None`, `system` is skipped and `None` is returned.
+    fn map_opt<SMarker, S, T>(
+        self,
+        system: impl IntoReactiveSystem<SMarker, System = S>,
+    ) -> MapOpt<Self::System, S>
+    where
+        Self: Sized,
+        Self::System: ReactiveSystem<Out = Option<T>>,
+        S: ReactiveSystem<In = T>,
+    {
+        MapOpt {
+            a: self.into_reactive_system(),
+            b: system.into_reactive_system(),
+        }
+    }
+
+    /// Run `system` after this system with the same input, ignoring both outputs.
+    ///
+    /// Unlike [`IntoReactiveSystem::map`], the systems only need to share an input type.
+    /// The chain changes when either system changes, and both systems run.
+    fn chain<SMarker, S>(
+        self,
+        system: impl IntoReactiveSystem<SMarker, System = S>,
+    ) -> Chain<Self::System, S>
+    where
+        Self: Sized,
+        <Self::System as ReactiveSystem>::In: Clone,
+        S: ReactiveSystem<In = <Self::System as ReactiveSystem>::In>,
+    {
+        Chain {
+            a: self.into_reactive_system(),
+            b: system.into_reactive_system(),
+        }
+    }
+
+    /// Pipe a clone of the output of this system into both `a` and `b`, ignoring their outputs.
+    ///
+    /// This lets one computation drive several effects without running it again.
+    /// The fan-out changes when any of the systems change.
+    fn fan_out<AMarker, BMarker, A, B>(
+        self,
+        a: impl IntoReactiveSystem<AMarker, System = A>,
+        b: impl IntoReactiveSystem<BMarker, System = B>,
+    ) -> FanOut<Self::System, A, B>
+    where
+        Self: Sized,
+        <Self::System as ReactiveSystem>::Out: Clone,
+        A: ReactiveSystem<In = <Self::System as ReactiveSystem>::Out>,
+        B: ReactiveSystem<In = <Self::System as ReactiveSystem>::Out>,
+    {
+        FanOut {
+            system: self.into_reactive_system(),
+            a: a.into_reactive_system(),
+            b: b.into_reactive_system(),
+        }
+    }
+
+    /// Pipe the output of this system into `system`, only if it's `Some`.
+    ///
+    /// When the output is `None`, `system` is skipped and the default output is returned.
+    fn and_then<SMarker, S, T>(
+        self,
+        system: impl IntoReactiveSystem<SMarker, System = S>,
+    ) -> AndThen<Self::System, S>
+    where
+        Self: Sized,
+        Self::System: ReactiveSystem<Out = Option<T>>,
+        S: ReactiveSystem<In = T>,
+        S::Out: Default,
+    {
+        AndThen {
+            a: self.into_reactive_system(),
+            b: system.into_reactive_system(),
+        }
+    }
+
+    /// Pipe the output of this system into `system`, only if it's `Ok`.
+    ///
+    /// When the output is an `Err`, `system` is skipped and the default output is returned.
+    fn and_then_ok<SMarker, S, T, E>(
+        self,
+        system: impl IntoReactiveSystem<SMarker, System = S>,
+    ) -> AndThenOk<Self::System, S>
+    where
+        Self: Sized,
+        Self::System: ReactiveSystem<Out = Result<T, E>>,
+        S: ReactiveSystem<In = T>,
+        S::Out: Default,
+    {
+        AndThenOk {
+            a: self.into_reactive_system(),
+            b: system.into_reactive_system(),
+        }
+    }
+
+    /// Transform the error of this system's output with `f`, leaving `Ok` outputs untouched.
+    ///
+    /// This lets systems with different error types be used in the same pipeline.
+    fn map_err<T, E, E2, F>(self, f: F) -> MapErr<Self::System, F>
+    where
+        Self: Sized,
+        Self::System: ReactiveSystem<Out = Result<T, E>>,
+        F: FnMut(E) -> E2 + Send + Sync,
+    {
+        MapErr {
+            system: self.into_reactive_system(),
+            f,
+        }
+    }
+
+    /// Transform the input of this system with `f` before it runs.
+    fn map_input<I, F>(self, f: F) -> MapInput<Self::System, F, I>
+    where
+        Self: Sized,
+        F: FnMut(I) -> <Self::System as ReactiveSystem>::In + Send + Sync,
+    {
+        MapInput {
+            system: self.into_reactive_system(),
+            f,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Also consider this system changed whenever `predicate` returns `true`.
+    ///
+    /// Unlike change detection on parameters, this can force a run without any changes.
+    fn dirty_when<F>(self, predicate: F) -> DirtyWhen<Self::System, F>
+    where
+        Self: Sized,
+        F: FnMut(&World) -> bool + Send + Sync,
+    {
+        DirtyWhen {
+            system: self.into_reactive_system(),
+            predicate,
+        }
+    }
+
+    /// Only consider this system changed once every `interval`, ignoring changes to its parameters.
+    ///
+    /// Elapsed time is read from the [`Time`] resource.
+    /// After a long frame, the system is only changed once instead of once per missed interval.
+    #[cfg(feature = "time")]
+    fn every(self, interval: Duration) -> Every<Self::System>
+    where
+        Self: Sized,
+    {
+        Every {
+            system: self.into_reactive_system(),
+            interval,
+            next: None,
+            polled_at: None,
+            is_due: false,
+        }
+    }
+
+    /// Only run this system once, and then never consider it changed again.
+    fn run_once(self) -> RunOnce<Self::System>
+    where
+        Self: Sized,
+    {
+        RunOnce {
+            system: self.into_reactive_system(),
+            finished: false,
+        }
+    }
+
+    /// Only run this system `n` times, and then never consider it changed again.
+    ///
+    /// Each run counts, including runs for different targets, but polls that find changes don't.
+    fn take(self, n: usize) -> Take<Self::System>
+    where
+        Self: Sized,
+    {
+        Take {
+            system: self.into_reactive_system(),
+            limit: n,
+            remaining: n,
+        }
+    }
 }
 
 impl<S: ReactiveSystem> IntoReactiveSystem<()> for S {
@@ -42,11 +257,22 @@ where
         FunctionReactiveSystem {
             f: self,
             state: None,
+            access: FilteredAccessSet::default(),
             _marker: PhantomData,
         }
     }
 }
 
+/// Box `system`, to store reactive systems of different types together.
+pub fn box_reactive<Marker, S>(
+    system: impl IntoReactiveSystem<Marker, System = S>,
+) -> BoxedReactiveSystem<S::In, S::Out>
+where
+    S: ReactiveSystem + 'static,
+{
+    Box::new(system.into_reactive_system())
+}
+
 pub struct Map<A, B> {
     a: A,
     b: B,
@@ -67,7 +293,18 @@ where
     }
 
     fn is_changed(&mut self, mut world: DeferredWorld) -> bool {
-        self.a.is_changed(world.reborrow()) || self.b.is_changed(world)
+        self.a.is_changed(world.reborrow()) | self.b.is_changed(world)
+    }
+
+    fn is_changed_with_entity(&mut self, mut world: DeferredWorld, entity: Entity) -> bool {
+        self.a.is_changed_with_entity(world.reborrow(), entity)
+            | self.b.is_changed_with_entity(world, entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        let mut access = self.a.access();
+        access.extend(self.b.access());
+        access
     }
 
     fn run(&mut self, input: Self::In, mut world: DeferredWorld, entity: Entity) -> Self::Out {
@@ -75,3 +312,1650 @@ where
         self.b.run(out, world, entity)
     }
 }
+
+pub struct WithEntity<S> {
+    system: S,
+}
+
+impl<S: ReactiveSystem> ReactiveSystem for WithEntity<S> {
+    type In = S::In;
+
+    type Out = (Entity, S::Out);
+
+    fn init(&mut self, world: &mut World) {
+        self.system.init(world);
+    }
+
+    fn is_changed(&mut self, world: DeferredWorld) -> bool {
+        self.system.is_changed(world)
+    }
+
+    fn is_changed_with_entity(&mut self, world: DeferredWorld, entity: Entity) -> bool {
+        self.system.is_changed_with_entity(world, entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        self.system.access()
+    }
+
+    fn run(&mut self, input: Self::In, world: DeferredWorld, entity: Entity) -> Self::Out {
+        (entity, self.system.run(input, world, entity))
+    }
+}
+
+pub struct Chain<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> ReactiveSystem for Chain<A, B>
+where
+    A: ReactiveSystem,
+    A::In: Clone,
+    B: ReactiveSystem<In = A::In>,
+{
+    type In = A::In;
+
+    type Out = ();
+
+    fn init(&mut self, world: &mut World) {
+        self.a.init(world);
+        self.b.init(world);
+    }
+
+    fn is_changed(&mut self, mut world: DeferredWorld) -> bool {
+        // Poll both systems, since both of them run.
+        self.a.is_changed(world.reborrow()) | self.b.is_changed(world)
+    }
+
+    fn is_changed_with_entity(&mut self, mut world: DeferredWorld, entity: Entity) -> bool {
+        self.a.is_changed_with_entity(world.reborrow(), entity)
+            | self.b.is_changed_with_entity(world, entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        let mut access = self.a.access();
+        access.extend(self.b.access());
+        access
+    }
+
+    fn run(&mut self, input: Self::In, mut world: DeferredWorld, entity: Entity) -> Self::Out {
+        self.a.run(input.clone(), world.reborrow(), entity);
+        self.b.run(input, world, entity);
+    }
+}
+
+pub struct FanOut<S, A, B> {
+    system: S,
+    a: A,
+    b: B,
+}
+
+impl<S, A, B> ReactiveSystem for FanOut<S, A, B>
+where
+    S: ReactiveSystem,
+    S::Out: Clone,
+    A: ReactiveSystem<In = S::Out>,
+    B: ReactiveSystem<In = S::Out>,
+{
+    type In = S::In;
+
+    type Out = ();
+
+    fn init(&mut self, world: &mut World) {
+        self.system.init(world);
+        self.a.init(world);
+        self.b.init(world);
+    }
+
+    fn is_changed(&mut self, mut world: DeferredWorld) -> bool {
+        // Poll every system, since all of them run.
+        self.system.is_changed(world.reborrow())
+            | self.a.is_changed(world.reborrow())
+            | self.b.is_changed(world)
+    }
+
+    fn is_changed_with_entity(&mut self, mut world: DeferredWorld, entity: Entity) -> bool {
+        self.system.is_changed_with_entity(world.reborrow(), entity)
+            | self.a.is_changed_with_entity(world.reborrow(), entity)
+            | self.b.is_changed_with_entity(world, entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        let mut access = self.system.access();
+        access.extend(self.a.access());
+        access.extend(self.b.access());
+        access
+    }
+
+    fn run(&mut self, input: Self::In, mut world: DeferredWorld, entity: Entity) -> Self::Out {
+        let out = self.system.run(input, world.reborrow(), entity);
+        self.a.run(out.clone(), world.reborrow(), entity);
+        self.b.run(out, world, entity);
+    }
+}
+
+pub struct MapOpt<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B, T> ReactiveSystem for MapOpt<A, B>
+where
+    A: ReactiveSystem<Out = Option<T>>,
+    B: ReactiveSystem<In = T>,
+{
+    type In = A::In;
+
+    type Out = Option<B::Out>;
+
+    fn init(&mut self, world: &mut World) {
+        self.a.init(world);
+        self.b.init(world);
+    }
+
+    fn is_changed(&mut self, mut world: DeferredWorld) -> bool {
+        self.a.is_changed(world.reborrow()) | self.b.is_changed(world)
+    }
+
+    fn is_changed_with_entity(&mut self, mut world: DeferredWorld, entity: Entity) -> bool {
+        self.a.is_changed_with_entity(world.reborrow(), entity)
+            | self.b.is_changed_with_entity(world, entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        let mut access = self.a.access();
+        access.extend(self.b.access());
+        access
+    }
+
+    fn run(&mut self, input: Self::In, mut world: DeferredWorld, entity: Entity) -> Self::Out {
+        let out = self.a.run(input, world.reborrow(), entity)?;
+        Some(self.b.run(out, world, entity))
+    }
+}
+
+pub struct AndThen<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B, T> ReactiveSystem for AndThen<A, B>
+where
+    A: ReactiveSystem<Out = Option<T>>,
+    B: ReactiveSystem<In = T>,
+    B::Out: Default,
+{
+    type In = A::In;
+
+    type Out = B::Out;
+
+    fn init(&mut self, world: &mut World) {
+        self.a.init(world);
+        self.b.init(world);
+    }
+
+    fn is_changed(&mut self, mut world: DeferredWorld) -> bool {
+        self.a.is_changed(world.reborrow()) | self.b.is_changed(world)
+    }
+
+    fn is_changed_with_entity(&mut self, mut world: DeferredWorld, entity: Entity) -> bool {
+        self.a.is_changed_with_entity(world.reborrow(), entity)
+            | self.b.is_changed_with_entity(world, entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        let mut access = self.a.access();
+        access.extend(self.b.access());
+        access
+    }
+
+    fn run(&mut self, input: Self::In, mut world: DeferredWorld, entity: Entity) -> Self::Out {
+        match self.a.run(input, world.reborrow(), entity) {
+            Some(out) => self.b.run(out, world, entity),
+            None => B::Out::default(),
+        }
+    }
+}
+
+pub struct AndThenOk<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B, T, E> ReactiveSystem for AndThenOk<A, B>
+where
+    A: ReactiveSystem<Out = Result<T, E>>,
+    B: ReactiveSystem<In = T>,
+    B::Out: Default,
+{
+    type In = A::In;
+
+    type Out = B::Out;
+
+    fn init(&mut self, world: &mut World) {
+        self.a.init(world);
+        self.b.init(world);
+    }
+
+    fn is_changed(&mut self, mut world: DeferredWorld) -> bool {
+        self.a.is_changed(world.reborrow()) | self.b.is_changed(world)
+    }
+
+    fn is_changed_with_entity(&mut self, mut world: DeferredWorld, entity: Entity) -> bool {
+        self.a.is_changed_with_entity(world.reborrow(), entity)
+            | self.b.is_changed_with_entity(world, entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        let mut access = self.a.access();
+        access.extend(self.b.access());
+        access
+    }
+
+    fn run(&mut self, input: Self::In, mut world: DeferredWorld, entity: Entity) -> Self::Out {
+        match self.a.run(input, world.reborrow(), entity) {
+            Ok(out) => self.b.run(out, world, entity),
+            Err(_) => B::Out::default(),
+        }
+    }
+}
+
+/// Reactive system that feeds `S` with input read from the world.
+pub struct WithInput<F, S> {
+    f: F,
+    system: S,
+}
+
+impl<F, S> WithInput<F, S> {
+    pub(crate) fn new(f: F, system: S) -> Self {
+        Self { f, system }
+    }
+}
+
+impl<F, S> ReactiveSystem for WithInput<F, S>
+where
+    F: FnMut(&World) -> S::In + Send + Sync,
+    S: ReactiveSystem,
+{
+    type In = ();
+
+    type Out = S::Out;
+
+    fn init(&mut self, world: &mut World) {
+        self.system.init(world);
+    }
+
+    fn is_changed(&mut self, world: DeferredWorld) -> bool {
+        self.system.is_changed(world)
+    }
+
+    fn is_changed_with_entity(&mut self, world: DeferredWorld, entity: Entity) -> bool {
+        self.system.is_changed_with_entity(world, entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        let mut access = self.system.access();
+        access.read_all();
+        access
+    }
+
+    fn run(&mut self, _input: Self::In, world: DeferredWorld, entity: Entity) -> Self::Out {
+        let input = (self.f)(&world);
+        self.system.run(input, world, entity)
+    }
+}
+
+pub struct MapErr<S, F> {
+    system: S,
+    f: F,
+}
+
+impl<S, F, T, E, E2> ReactiveSystem for MapErr<S, F>
+where
+    S: ReactiveSystem<Out = Result<T, E>>,
+    F: FnMut(E) -> E2 + Send + Sync,
+{
+    type In = S::In;
+
+    type Out = Result<T, E2>;
+
+    fn init(&mut self, world: &mut World) {
+        self.system.init(world);
+    }
+
+    fn is_changed(&mut self, world: DeferredWorld) -> bool {
+        self.system.is_changed(world)
+    }
+
+    fn is_changed_with_entity(&mut self, world: DeferredWorld, entity: Entity) -> bool {
+        self.system.is_changed_with_entity(world, entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        self.system.access()
+    }
+
+    fn run(&mut self, input: Self::In, world: DeferredWorld, entity: Entity) -> Self::Out {
+        self.system.run(input, world, entity).map_err(&mut self.f)
+    }
+}
+
+pub struct MapInput<S, F, I> {
+    system: S,
+    f: F,
+    _marker: PhantomData<fn(I)>,
+}
+
+impl<S, F, I> ReactiveSystem for MapInput<S, F, I>
+where
+    S: ReactiveSystem,
+    F: FnMut(I) -> S::In + Send + Sync,
+{
+    type In = I;
+
+    type Out = S::Out;
+
+    fn init(&mut self, world: &mut World) {
+        self.system.init(world);
+    }
+
+    fn is_changed(&mut self, world: DeferredWorld) -> bool {
+        self.system.is_changed(world)
+    }
+
+    fn is_changed_with_entity(&mut self, world: DeferredWorld, entity: Entity) -> bool {
+        self.system.is_changed_with_entity(world, entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        self.system.access()
+    }
+
+    fn run(&mut self, input: Self::In, world: DeferredWorld, entity: Entity) -> Self::Out {
+        self.system.run((self.f)(input), world, entity)
+    }
+}
+
+pub struct DirtyWhen<S, F> {
+    system: S,
+    predicate: F,
+}
+
+impl<S, F> ReactiveSystem for DirtyWhen<S, F>
+where
+    S: ReactiveSystem,
+    F: FnMut(&World) -> bool + Send + Sync,
+{
+    type In = S::In;
+
+    type Out = S::Out;
+
+    fn init(&mut self, world: &mut World) {
+        self.system.init(world);
+    }
+
+    fn is_changed(&mut self, world: DeferredWorld) -> bool {
+        (self.predicate)(&world) || self.system.is_changed(world)
+    }
+
+    fn is_changed_with_entity(&mut self, world: DeferredWorld, entity: Entity) -> bool {
+        (self.predicate)(&world) || self.system.is_changed_with_entity(world, entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        let mut access = self.system.access();
+        access.read_all();
+        access
+    }
+
+    fn run(&mut self, input: Self::In, world: DeferredWorld, entity: Entity) -> Self::Out {
+        self.system.run(input, world, entity)
+    }
+}
+
+pub struct RunOnce<S> {
+    system: S,
+    finished: bool,
+}
+
+impl<S: ReactiveSystem> ReactiveSystem for RunOnce<S> {
+    type In = S::In;
+
+    type Out = S::Out;
+
+    fn init(&mut self, world: &mut World) {
+        self.finished = false;
+        self.system.init(world);
+    }
+
+    fn is_changed(&mut self, world: DeferredWorld) -> bool {
+        !self.finished && self.system.is_changed(world)
+    }
+
+    fn is_changed_with_entity(&mut self, world: DeferredWorld, entity: Entity) -> bool {
+        !self.finished && self.system.is_changed_with_entity(world, entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        self.system.access()
+    }
+
+    fn run(&mut self, input: Self::In, world: DeferredWorld, entity: Entity) -> Self::Out {
+        self.finished = true;
+        self.system.run(input, world, entity)
+    }
+}
+
+pub struct Take<S> {
+    system: S,
+    limit: usize,
+    remaining: usize,
+}
+
+impl<S: ReactiveSystem> ReactiveSystem for Take<S> {
+    type In = S::In;
+
+    type Out = S::Out;
+
+    fn init(&mut self, world: &mut World) {
+        self.remaining = self.limit;
+        self.system.init(world);
+    }
+
+    fn is_changed(&mut self, world: DeferredWorld) -> bool {
+        self.remaining > 0 && self.system.is_changed(world)
+    }
+
+    fn is_changed_with_entity(&mut self, world: DeferredWorld, entity: Entity) -> bool {
+        self.remaining > 0 && self.system.is_changed_with_entity(world, entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        self.system.access()
+    }
+
+    fn run(&mut self, input: Self::In, world: DeferredWorld, entity: Entity) -> Self::Out {
+        self.remaining = self.remaining.saturating_sub(1);
+        self.system.run(input, world, entity)
+    }
+}
+
+#[cfg(feature = "time")]
+pub struct Every<S> {
+    system: S,
+    interval: Duration,
+    next: Option<Duration>,
+    polled_at: Option<Duration>,
+    is_due: bool,
+}
+
+#[cfg(feature = "time")]
+impl<S> Every<S> {
+    /// Returns `true` if the interval has elapsed, checking at most once per frame
+    /// so every target sees the same result.
+    fn poll(&mut self, world: &World) -> bool {
+        let Some(time) = world.get_resource::<Time>() else {
+            return false;
+        };
+        let now = time.elapsed();
+        if self.polled_at == Some(now) {
+            return self.is_due;
+        }
+        self.polled_at = Some(now);
+
+        let next = *self.next.get_or_insert(now + self.interval);
+        self.is_due = now >= next;
+        if self.is_due {
+            self.next = Some(now + self.interval);
+        }
+        self.is_due
+    }
+}
+
+#[cfg(feature = "time")]
+impl<S: ReactiveSystem> ReactiveSystem for Every<S> {
+    type In = S::In;
+
+    type Out = S::Out;
+
+    fn init(&mut self, world: &mut World) {
+        // Restart the interval.
+        self.next = None;
+        self.polled_at = None;
+        self.is_due = false;
+        self.system.init(world);
+    }
+
+    fn is_changed(&mut self, world: DeferredWorld) -> bool {
+        self.poll(&world)
+    }
+
+    fn is_changed_with_entity(&mut self, world: DeferredWorld, entity: Entity) -> bool {
+        let _ = entity;
+        self.poll(&world)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        let mut access = self.system.access();
+        access.read_all();
+        access
+    }
+
+    fn run(&mut self, input: Self::In, world: DeferredWorld, entity: Entity) -> Self::Out {
+        self.system.run(input, world, entity)
+    }
+}
+
+pub struct FromWorldSystem<F, S> {
+    f: Option<F>,
+    system: Option<S>,
+}
+
+impl<F, S> FromWorldSystem<F, S> {
+    pub(crate) fn new(f: F) -> Self {
+        Self {
+            f: Some(f),
+            system: None,
+        }
+    }
+}
+
+impl<F, S> ReactiveSystem for FromWorldSystem<F, S>
+where
+    F: FnOnce(&mut World) -> S + Send + Sync,
+    S: ReactiveSystem,
+{
+    type In = S::In;
+
+    type Out = S::Out;
+
+    fn init(&mut self, world: &mut World) {
+        // Only build the system once, later inits (e.g. from `reset_on_enter`) reuse it.
+        if let Some(f) = self.f.take() {
+            self.system = Some(f(world));
+        }
+        self.system
+            .as_mut()
+            .expect("FromWorldSystem has been initialized")
+            .init(world);
+    }
+
+    fn is_changed(&mut self, world: DeferredWorld) -> bool {
+        self.system
+            .as_mut()
+            .is_some_and(|system| system.is_changed(world))
+    }
+
+    fn is_changed_with_entity(&mut self, world: DeferredWorld, entity: Entity) -> bool {
+        self.system
+            .as_mut()
+            .is_some_and(|system| system.is_changed_with_entity(world, entity))
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        match &self.system {
+            Some(system) => system.access(),
+            None => write_all_access(),
+        }
+    }
+
+    fn run(&mut self, input: Self::In, world: DeferredWorld, entity: Entity) -> Self::Out {
+        self.system
+            .as_mut()
+            .expect("FromWorldSystem must be initialized before running")
+            .run(input, world, entity)
+    }
+}
+
+pub struct OnRemoved<T, S> {
+    system: S,
+    reader: ManualEventReader<RemovedComponentEntity>,
+    removed: EntityHashSet,
+    has_targets: bool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Component, S> OnRemoved<T, S> {
+    pub(crate) fn new(system: S) -> Self {
+        Self {
+            system,
+            reader: ManualEventReader::default(),
+            removed: EntityHashSet::default(),
+            has_targets: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Read the entities that lost `T` since the last poll,
+    /// returning `true` if there were any.
+    fn poll(&mut self, world: &World) -> bool {
+        let Some(events) = world
+            .components()
+            .component_id::<T>()
+            .and_then(|id| world.removed_components().get(id))
+        else {
+            return false;
+        };
+
+        // Replace the last batch, so entities that aren't targets don't pile up.
+        let mut removed = self.reader.read(events).peekable();
+        if removed.peek().is_none() {
+            return false;
+        }
+        self.removed = removed.map(|entity| entity.clone().into()).collect();
+        true
+    }
+}
+
+impl<T, S> ReactiveSystem for OnRemoved<T, S>
+where
+    T: Component,
+    S: ReactiveSystem<In = Vec<Entity>, Out = ()>,
+{
+    type In = ();
+
+    type Out = ();
+
+    fn init(&mut self, world: &mut World) {
+        world.init_component::<T>();
+        self.system.init(world);
+    }
+
+    fn is_changed(&mut self, mut world: DeferredWorld) -> bool {
+        // Keep the system up to date, but only run when `T` is removed.
+        self.system.is_changed(world.reborrow());
+        self.poll(&world)
+    }
+
+    fn is_changed_with_entity(&mut self, mut world: DeferredWorld, entity: Entity) -> bool {
+        self.has_targets = true;
+        self.system.is_changed_with_entity(world.reborrow(), entity);
+        self.poll(&world);
+        self.removed.contains(&entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        self.system.access()
+    }
+
+    fn run(&mut self, _input: Self::In, world: DeferredWorld, entity: Entity) -> Self::Out {
+        // Targets only see their own removal, otherwise every removed entity is passed.
+        let removed: Vec<_> = if self.has_targets {
+            self.removed.take(&entity).into_iter().collect()
+        } else {
+            self.removed.drain().collect()
+        };
+
+        // Skip forced runs, like the first one, when nothing was removed.
+        if !removed.is_empty() {
+            self.system.run(removed, world, entity);
+        }
+    }
+}
+
+/// Tuple of reactive systems that can be combined with [`combine_latest`].
+pub trait LatestSources: Send + Sync {
+    type In;
+
+    type Out;
+
+    /// Latest output of each source, or `None` if it has to run again.
+    type Cache: Default + Send + Sync;
+
+    fn init(&mut self, world: &mut World);
+
+    /// Poll every source for `entity`, or at all if there's no target,
+    /// clearing the outputs of changed sources in `cache`.
+    fn is_changed(
+        &mut self,
+        world: DeferredWorld,
+        entity: Option<Entity>,
+        cache: &mut EntityHashMap<Self::Cache>,
+    ) -> bool;
+
+    fn access(&self) -> FilteredAccessSet<ComponentId>;
+
+    /// Run each source without a cached output, and return a clone of every output.
+    fn run(
+        &mut self,
+        input: Self::In,
+        world: DeferredWorld,
+        entity: Entity,
+        cache: &mut Self::Cache,
+    ) -> Self::Out;
+}
+
+/// Conversion into [`LatestSources`], for tuples of [`IntoReactiveSystem`].
+pub trait IntoLatestSources<Marker> {
+    type Sources: LatestSources;
+
+    fn into_latest_sources(self) -> Self::Sources;
+}
+
+macro_rules! impl_latest_sources {
+    ($(($s:ident, $m:ident, $i:tt)),*) => {
+        impl<In: Clone, $($s),*> LatestSources for ($($s,)*)
+        where
+            $($s: ReactiveSystem<In = In>, $s::Out: Clone + Send + Sync,)*
+        {
+            type In = In;
+
+            type Out = ($($s::Out,)*);
+
+            type Cache = ($(Option<$s::Out>,)*);
+
+            fn init(&mut self, world: &mut World) {
+                $(self.$i.init(world);)*
+            }
+
+            fn is_changed(
+                &mut self,
+                mut world: DeferredWorld,
+                entity: Option<Entity>,
+                cache: &mut EntityHashMap<Self::Cache>,
+            ) -> bool {
+                let mut is_changed = false;
+                $(
+                    let is_source_changed = match entity {
+                        Some(entity) => self.$i.is_changed_with_entity(world.reborrow(), entity),
+                        None => self.$i.is_changed(world.reborrow()),
+                    };
+                    if is_source_changed {
+                        match entity {
+                            Some(entity) => {
+                                if let Some(cache) = cache.get_mut(&entity) {
+                                    cache.$i = None;
+                                }
+                            }
+                            None => cache.values_mut().for_each(|cache| cache.$i = None),
+                        }
+                    }
+                    is_changed |= is_source_changed;
+                )*
+                is_changed
+            }
+
+            fn access(&self) -> FilteredAccessSet<ComponentId> {
+                let mut access = FilteredAccessSet::default();
+                $(access.extend(self.$i.access());)*
+                access
+            }
+
+            fn run(
+                &mut self,
+                input: Self::In,
+                mut world: DeferredWorld,
+                entity: Entity,
+                cache: &mut Self::Cache,
+            ) -> Self::Out {
+                ($(
+                    cache
+                        .$i
+                        .get_or_insert_with(|| self.$i.run(input.clone(), world.reborrow(), entity))
+                        .clone(),
+                )*)
+            }
+        }
+
+        impl<$($s, $m),*> IntoLatestSources<($($m,)*)> for ($($s,)*)
+        where
+            $($s: IntoReactiveSystem<$m>,)*
+            ($($s::System,)*): LatestSources,
+        {
+            type Sources = ($($s::System,)*);
+
+            fn into_latest_sources(self) -> Self::Sources {
+                ($(self.$i.into_reactive_system(),)*)
+            }
+        }
+    };
+}
+
+impl_latest_sources!((A, AM, 0), (B, BM, 1));
+impl_latest_sources!((A, AM, 0), (B, BM, 1), (C, CM, 2));
+impl_latest_sources!((A, AM, 0), (B, BM, 1), (C, CM, 2), (D, DM, 3));
+impl_latest_sources!((A, AM, 0), (B, BM, 1), (C, CM, 2), (D, DM, 3), (E, EM, 4));
+impl_latest_sources!(
+    (A, AM, 0),
+    (B, BM, 1),
+    (C, CM, 2),
+    (D, DM, 3),
+    (E, EM, 4),
+    (F, FM, 5)
+);
+
+/// Combine a tuple of systems into a system that outputs a tuple of their latest outputs.
+///
+/// The combined system changes when any source changes, but only the changed sources run again.
+/// The other outputs are cloned from the last run, for each target.
+pub fn combine_latest<Marker, T: IntoLatestSources<Marker>>(
+    sources: T,
+) -> CombineLatest<T::Sources> {
+    CombineLatest {
+        sources: sources.into_latest_sources(),
+        cache: EntityHashMap::default(),
+    }
+}
+
+pub struct CombineLatest<T: LatestSources> {
+    sources: T,
+    cache: EntityHashMap<T::Cache>,
+}
+
+impl<T: LatestSources> ReactiveSystem for CombineLatest<T> {
+    type In = T::In;
+
+    type Out = T::Out;
+
+    fn init(&mut self, world: &mut World) {
+        self.cache.clear();
+        self.sources.init(world);
+    }
+
+    fn is_changed(&mut self, world: DeferredWorld) -> bool {
+        self.sources.is_changed(world, None, &mut self.cache)
+    }
+
+    fn is_changed_with_entity(&mut self, world: DeferredWorld, entity: Entity) -> bool {
+        self.sources
+            .is_changed(world, Some(entity), &mut self.cache)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        self.sources.access()
+    }
+
+    fn run(&mut self, input: Self::In, world: DeferredWorld, entity: Entity) -> Self::Out {
+        let cache = self.cache.entry(entity).or_default();
+        self.sources.run(input, world, entity, cache)
+    }
+}
+
+pub struct OnChanged<T: Component, S> {
+    system: S,
+    query: Option<QueryState<(Entity, Ref<'static, T>)>>,
+    last_run: Tick,
+    changed: EntityHashSet,
+    has_targets: bool,
+}
+
+impl<T: Component, S> OnChanged<T, S> {
+    pub(crate) fn new(system: S) -> Self {
+        Self {
+            system,
+            query: None,
+            last_run: Tick::new(0),
+            changed: EntityHashSet::default(),
+            has_targets: false,
+        }
+    }
+
+    /// Find the entities whose `T` changed since the last poll,
+    /// returning `true` if there were any.
+    fn poll(&mut self, world: &DeferredWorld) -> bool {
+        let Some(query) = &mut self.query else {
+            return false;
+        };
+        let this_run = world.increment_change_tick();
+        let last_run = std::mem::replace(&mut self.last_run, this_run);
+
+        query.update_archetypes(world);
+        let mut changed = query
+            .iter_manual(world)
+            .filter(|(_, value)| value.last_changed().is_newer_than(last_run, this_run))
+            .map(|(entity, _)| entity)
+            .peekable();
+        if changed.peek().is_none() {
+            return false;
+        }
+
+        // Replace the last batch, so entities that aren't targets don't pile up.
+        self.changed = changed.collect();
+        true
+    }
+}
+
+impl<T, S> ReactiveSystem for OnChanged<T, S>
+where
+    T: Component + Clone,
+    S: ReactiveSystem<In = T, Out = ()>,
+{
+    type In = ();
+
+    type Out = ();
+
+    fn init(&mut self, world: &mut World) {
+        self.query = Some(QueryState::new(world));
+        // Treat everything as changed on the first poll, like a new system.
+        self.last_run = Tick::new(world.change_tick().get().wrapping_sub(Tick::MAX.get()));
+        self.changed.clear();
+        self.system.init(world);
+    }
+
+    fn is_changed(&mut self, mut world: DeferredWorld) -> bool {
+        // Keep the system up to date, but only run when `T` changes.
+        self.system.is_changed(world.reborrow());
+        self.poll(&world)
+    }
+
+    fn is_changed_with_entity(&mut self, mut world: DeferredWorld, entity: Entity) -> bool {
+        self.has_targets = true;
+        self.system.is_changed_with_entity(world.reborrow(), entity);
+        self.poll(&world);
+        self.changed.contains(&entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        let mut access = self.system.access();
+        if let Some(query) = &self.query {
+            access.add(query.component_access().clone());
+        }
+        access
+    }
+
+    fn run(&mut self, _input: Self::In, mut world: DeferredWorld, entity: Entity) -> Self::Out {
+        // Targets only run for their own change, otherwise the system runs for each changed entity.
+        let changed: Vec<_> = if self.has_targets {
+            self.changed.take(&entity).into_iter().collect()
+        } else {
+            self.changed.drain().collect()
+        };
+
+        for entity in changed {
+            // The value is cloned when the system runs, so it includes any earlier changes this frame.
+            if let Some(value) = world.get::<T>(entity).cloned() {
+                self.system.run(value, world.reborrow(), entity);
+            }
+        }
+    }
+}
+
+pub struct AsyncReaction<S, C> {
+    system: S,
+    access: FilteredAccessSet<ComponentId>,
+    in_flight: EntityHashSet,
+    rerun: EntityHashSet,
+    completed: Arc<Mutex<Vec<(Entity, C)>>>,
+}
+
+impl<S, C> AsyncReaction<S, C> {
+    pub(crate) fn new(system: S) -> Self {
+        Self {
+            system,
+            access: write_all_access(),
+            in_flight: EntityHashSet::default(),
+            rerun: EntityHashSet::default(),
+            completed: Arc::default(),
+        }
+    }
+}
+
+impl<S, C: Command> AsyncReaction<S, C> {
+    /// Queue the commands of finished tasks.
+    fn poll(&mut self, world: &mut DeferredWorld) {
+        let completed = std::mem::take(
+            &mut *self
+                .completed
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        for (entity, command) in completed {
+            self.in_flight.remove(&entity);
+            world.commands().add(command);
+        }
+    }
+
+    /// Returns `true` if the system changed while the task for `entity` was in flight,
+    /// and that task has finished.
+    fn take_rerun(&mut self, entity: Option<Entity>) -> bool {
+        match entity {
+            Some(entity) => !self.in_flight.contains(&entity) && self.rerun.remove(&entity),
+            None => {
+                let in_flight = &self.in_flight;
+                let len = self.rerun.len();
+                self.rerun.retain(|entity| in_flight.contains(entity));
+                self.rerun.len() != len
+            }
+        }
+    }
+}
+
+impl<S, F, C> ReactiveSystem for AsyncReaction<S, C>
+where
+    S: ReactiveSystem<Out = F>,
+    F: Future<Output = C> + Send + 'static,
+    C: Command,
+{
+    type In = S::In;
+
+    type Out = ();
+
+    fn init(&mut self, world: &mut World) {
+        self.system.init(world);
+
+        // Finished tasks queue their commands when polled.
+        self.access = self.system.access();
+        add_deferred_access(world, &mut self.access);
+    }
+
+    fn is_changed(&mut self, mut world: DeferredWorld) -> bool {
+        self.poll(&mut world);
+        self.system.is_changed(world) | self.take_rerun(None)
+    }
+
+    fn is_changed_with_entity(&mut self, mut world: DeferredWorld, entity: Entity) -> bool {
+        self.poll(&mut world);
+        self.system.is_changed_with_entity(world, entity) | self.take_rerun(Some(entity))
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        self.access.clone()
+    }
+
+    fn run(&mut self, input: Self::In, world: DeferredWorld, entity: Entity) -> Self::Out {
+        // Run again once the task in flight finishes, instead of starting another one.
+        if self.in_flight.contains(&entity) {
+            self.rerun.insert(entity);
+            return;
+        }
+
+        let future = self.system.run(input, world, entity);
+        self.in_flight.insert(entity);
+
+        let completed = self.completed.clone();
+        IoTaskPool::get_or_init(TaskPool::default)
+            .spawn(async move {
+                let command = future.await;
+                completed
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push((entity, command));
+            })
+            .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::ReactionTestApp, Reaction, ReactionPlugin, Scope};
+    use std::{
+        mem,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    #[derive(Resource)]
+    struct Source(u32);
+
+    #[derive(Component)]
+    struct Health(i32);
+
+    #[derive(Component)]
+    struct Mana(i32);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Doubled(u32);
+
+    #[test]
+    fn async_access_includes_commands() {
+        let mut world = World::new();
+        world.insert_resource(Source(0));
+
+        let mut system = AsyncReaction::new(
+            (|_: In<Scope>, _: Res<Source>| async { |_: &mut World| {} }).into_reactive_system(),
+        );
+        system.init(&mut world);
+
+        let mut commands = FilteredAccessSet::default();
+        add_deferred_access(&mut world, &mut commands);
+        assert!(!system.access().is_compatible(&commands));
+    }
+
+    #[test]
+    fn async_writes_back() {
+        let mut app = ReactionTestApp::with_plugin(ReactionPlugin::new().parallel());
+        app.world_mut().insert_resource(Source(1));
+        let target = app.spawn_target(());
+        app.spawn(Reaction::new_async(
+            move |_: In<Scope>, source: Res<Source>| {
+                let value = source.0;
+                async move {
+                    move |world: &mut World| {
+                        world.entity_mut(target).insert(Doubled(value * 2));
+                    }
+                }
+            },
+        ));
+
+        let wait_for = |app: &mut ReactionTestApp, expected: u32| {
+            for _ in 0..1_000 {
+                app.step();
+                if app.world().get::<Doubled>(target) == Some(&Doubled(expected)) {
+                    return;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+            panic!("async reaction never wrote {expected}");
+        };
+        wait_for(&mut app, 2);
+
+        app.world_mut().resource_mut::<Source>().0 = 5;
+        wait_for(&mut app, 10);
+    }
+
+    #[test]
+    fn map_polls_both_systems() {
+        let mut app = ReactionTestApp::new();
+        let entity = app.spawn_target((Health(10), Mana(10)));
+        let reaction = app.spawn(Reaction::new(IntoReactiveSystem::map(
+            |_: In<Scope>, _: Query<&Health>| {},
+            |_: In<Scope>, _: Query<&Mana>| {},
+        )));
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+
+        let mut target = app.world_mut().entity_mut(entity);
+        target.get_mut::<Health>().unwrap().0 -= 1;
+        target.get_mut::<Mana>().unwrap().0 -= 1;
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+
+        // Both changes were seen in the same poll, so nothing is left to run for.
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn every_catches_up_once() {
+        let mut app = ReactionTestApp::new();
+        let a = app.spawn_target(());
+        let b = app.spawn_target(());
+        let mut reaction =
+            Reaction::every(Duration::from_millis(100), |_: In<Scope>, _: Commands| {});
+        reaction.add_target(a).add_target(b);
+        let reaction = app.spawn(reaction);
+        app.step();
+        let runs = app.runs(reaction);
+
+        app.advance(Duration::from_millis(60));
+        assert_eq!(app.runs(reaction), runs);
+
+        // Each target runs once per interval.
+        app.advance(Duration::from_millis(60));
+        assert_eq!(app.runs(reaction), runs + 2);
+
+        // A long frame misses two intervals, but only runs once.
+        app.advance(Duration::from_millis(240));
+        assert_eq!(app.runs(reaction), runs + 4);
+
+        app.advance(Duration::from_millis(60));
+        assert_eq!(app.runs(reaction), runs + 4);
+        app.advance(Duration::from_millis(60));
+        assert_eq!(app.runs(reaction), runs + 6);
+    }
+
+    #[derive(Resource)]
+    struct Offset(u32);
+
+    #[derive(Resource)]
+    struct Output(u32);
+
+    #[test]
+    fn with_input_reads_the_world() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().insert_resource(Source(1));
+        app.world_mut().insert_resource(Offset(10));
+        let reaction = app.spawn(Reaction::with_input(
+            |world: &World| world.resource::<Offset>().0,
+            |scope: In<Scope<u32>>, source: Res<Source>, mut commands: Commands| {
+                commands.insert_resource(Output(source.0 + scope.input));
+            },
+        ));
+        app.step();
+        assert_eq!(app.world().resource::<Output>().0, 11);
+
+        // The input is read each run, but changing it alone doesn't run the reaction.
+        app.world_mut().resource_mut::<Offset>().0 = 20;
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+
+        app.world_mut().resource_mut::<Source>().0 = 2;
+        app.step();
+        assert_eq!(app.world().resource::<Output>().0, 22);
+    }
+
+    #[test]
+    fn run_once_only_runs_for_the_first_change() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().insert_resource(Source(0));
+        let reaction =
+            app.spawn(Reaction::run_once(|_: In<Scope>, _: Res<Source>| {}).skip_initial());
+        app.step_n(2);
+        assert_eq!(app.runs(reaction), 0);
+
+        app.world_mut().resource_mut::<Source>().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+
+        app.world_mut().resource_mut::<Source>().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+    }
+
+    fn set_output(scope: In<Scope<u32>>, mut commands: Commands) {
+        commands.insert_resource(Output(scope.input));
+    }
+
+    #[test]
+    fn and_then_skips_none_and_err() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().insert_resource(Source(2));
+        let reaction = app.spawn(Reaction::new(
+            (|_: In<Scope>, source: Res<Source>| Some(source.0).filter(|value| *value != 3))
+                .and_then(set_output),
+        ));
+        app.step();
+        assert_eq!(app.world().resource::<Output>().0, 2);
+
+        app.world_mut().resource_mut::<Source>().0 = 3;
+        app.step();
+        assert_eq!(app.world().resource::<Output>().0, 2);
+
+        app.world_mut().despawn(reaction);
+        app.world_mut().remove_resource::<Output>();
+        app.spawn(Reaction::new(
+            (|_: In<Scope>, source: Res<Source>| source.0.checked_sub(4).ok_or(()))
+                .and_then_ok(set_output),
+        ));
+        app.world_mut().resource_mut::<Source>().0 = 1;
+        app.step();
+        assert!(!app.world().contains_resource::<Output>());
+
+        app.world_mut().resource_mut::<Source>().0 = 5;
+        app.step();
+        assert_eq!(app.world().resource::<Output>().0, 1);
+    }
+
+    #[derive(Component, Clone)]
+    struct Score(u32);
+
+    #[test]
+    fn on_changed_runs_for_each_changed_entity() {
+        let mut app = ReactionTestApp::new();
+        let a = app.spawn_target(Score(1));
+        let b = app.spawn_target(Score(2));
+        app.spawn(Reaction::on_changed::<Score, _, _>(
+            |scope: In<Scope<Score>>, mut commands: Commands| {
+                let Scope { entity, input } = scope.0;
+                commands.entity(entity).insert(Doubled(input.0 * 2));
+            },
+        ));
+        app.step();
+        assert_eq!(app.world().get::<Doubled>(a), Some(&Doubled(2)));
+        assert_eq!(app.world().get::<Doubled>(b), Some(&Doubled(4)));
+
+        app.world_mut().entity_mut(b).remove::<Doubled>();
+        app.world_mut().get_mut::<Score>(a).unwrap().0 = 5;
+        app.step();
+        assert_eq!(app.world().get::<Doubled>(a), Some(&Doubled(10)));
+        assert_eq!(app.world().get::<Doubled>(b), None);
+
+        let c = app.spawn_target(Score(3));
+        app.step();
+        assert_eq!(app.world().get::<Doubled>(c), Some(&Doubled(6)));
+        assert_eq!(app.world().get::<Doubled>(b), None);
+    }
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Total(i32);
+
+    #[test]
+    fn combine_latest_only_reruns_changed_sources() {
+        let mut app = ReactionTestApp::new();
+        let a = app.spawn_target((Health(10), Mana(1)));
+        let b = app.spawn_target((Health(20), Mana(2)));
+        let health_runs = Arc::new(AtomicUsize::new(0));
+        let mana_runs = Arc::new(AtomicUsize::new(0));
+        let (health_counter, mana_counter) = (health_runs.clone(), mana_runs.clone());
+        let mut reaction = Reaction::combine_latest(
+            (
+                move |scope: In<Scope>, query: Query<&Health>| {
+                    health_counter.fetch_add(1, Ordering::Relaxed);
+                    query.get(scope.entity).unwrap().0
+                },
+                move |scope: In<Scope>, query: Query<&Mana>| {
+                    mana_counter.fetch_add(1, Ordering::Relaxed);
+                    query.get(scope.entity).unwrap().0
+                },
+            ),
+            |scope: In<Scope<(i32, i32)>>, mut commands: Commands| {
+                let Scope { entity, input } = scope.0;
+                commands.entity(entity).insert(Total(input.0 + input.1));
+            },
+        );
+        reaction.add_target(a).add_target(b);
+        app.spawn(reaction);
+        app.step();
+        assert_eq!(health_runs.load(Ordering::Relaxed), 2);
+        assert_eq!(mana_runs.load(Ordering::Relaxed), 2);
+        assert_eq!(app.world().get::<Total>(a), Some(&Total(11)));
+        assert_eq!(app.world().get::<Total>(b), Some(&Total(22)));
+
+        // Only the health of `a` runs again, its mana is cloned from the cache.
+        app.world_mut().get_mut::<Health>(a).unwrap().0 = 30;
+        app.step();
+        assert_eq!(health_runs.load(Ordering::Relaxed), 3);
+        assert_eq!(mana_runs.load(Ordering::Relaxed), 2);
+        assert_eq!(app.world().get::<Total>(a), Some(&Total(31)));
+        assert_eq!(app.world().get::<Total>(b), Some(&Total(22)));
+
+        app.world_mut().get_mut::<Mana>(b).unwrap().0 = 5;
+        app.step();
+        assert_eq!(health_runs.load(Ordering::Relaxed), 3);
+        assert_eq!(mana_runs.load(Ordering::Relaxed), 3);
+        assert_eq!(app.world().get::<Total>(a), Some(&Total(31)));
+        assert_eq!(app.world().get::<Total>(b), Some(&Total(25)));
+    }
+
+    #[derive(Default, Resource)]
+    struct Removed(Vec<(Entity, Vec<Entity>)>);
+
+    fn record_removed(scope: In<Scope<Vec<Entity>>>, mut commands: Commands) {
+        let Scope { entity, mut input } = scope.0;
+        input.sort();
+        commands.add(move |world: &mut World| {
+            world.resource_mut::<Removed>().0.push((entity, input));
+        });
+    }
+
+    fn take_removed(app: &mut ReactionTestApp) -> Vec<(Entity, Vec<Entity>)> {
+        mem::take(&mut app.world_mut().resource_mut::<Removed>().0)
+    }
+
+    #[test]
+    fn on_removed_runs_once_for_every_removal() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Removed>();
+        let a = app.spawn_target(Score(1));
+        let b = app.spawn_target(Score(2));
+        app.spawn_target(Score(3));
+        let reaction = app.spawn(Reaction::on_removed::<Score, _, _>(record_removed));
+        app.step();
+        assert_eq!(take_removed(&mut app), vec![]);
+
+        app.world_mut().entity_mut(a).remove::<Score>();
+        app.world_mut().entity_mut(b).remove::<Score>();
+        app.step();
+        assert_eq!(take_removed(&mut app), vec![(reaction, vec![a, b])]);
+
+        app.step();
+        assert_eq!(take_removed(&mut app), vec![]);
+    }
+
+    #[test]
+    fn on_removed_runs_for_each_target() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Removed>();
+        let a = app.spawn_target(Score(1));
+        let b = app.spawn_target(Score(2));
+        let c = app.spawn_target(Score(3));
+        let mut reaction = Reaction::on_removed::<Score, _, _>(record_removed);
+        reaction.add_target(a).add_target(b);
+        app.spawn(reaction);
+        app.step();
+        assert_eq!(take_removed(&mut app), vec![]);
+
+        // `c` isn't a target, so its removal is ignored.
+        app.world_mut().entity_mut(a).remove::<Score>();
+        app.world_mut().entity_mut(c).remove::<Score>();
+        app.step();
+        assert_eq!(take_removed(&mut app), vec![(a, vec![a])]);
+
+        app.world_mut().entity_mut(b).remove::<Score>();
+        app.step();
+        assert_eq!(take_removed(&mut app), vec![(b, vec![b])]);
+    }
+
+    #[test]
+    fn take_limits_runs_across_targets() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().insert_resource(Source(0));
+        let a = app.spawn_target(());
+        let b = app.spawn_target(());
+        let mut reaction = Reaction::new((|_: In<Scope>, _: Res<Source>| {}).take(3));
+        reaction.add_target(a).add_target(b);
+        let reaction = app.spawn(reaction);
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+
+        // Only one run is left, so the second target is skipped.
+        app.world_mut().resource_mut::<Source>().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), 3);
+
+        app.world_mut().resource_mut::<Source>().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), 3);
+    }
+
+    #[test]
+    fn fan_out_runs_the_source_once_for_both_systems() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().insert_resource(Source(1));
+        app.world_mut().insert_resource(Offset(10));
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counter = runs.clone();
+        let target = app.spawn_target(());
+        let mut reaction = Reaction::new(
+            (move |_: In<Scope>, source: Res<Source>| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                source.0
+            })
+            .fan_out(
+                |scope: In<Scope<u32>>, mut commands: Commands| {
+                    commands
+                        .entity(scope.entity)
+                        .insert(Doubled(scope.input * 2));
+                },
+                |scope: In<Scope<u32>>, offset: Res<Offset>, mut commands: Commands| {
+                    commands.insert_resource(Output(scope.input + offset.0));
+                },
+            ),
+        );
+        reaction.add_target(target);
+        app.spawn(reaction);
+        app.step();
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+        assert_eq!(app.world().get::<Doubled>(target), Some(&Doubled(2)));
+        assert_eq!(app.world().resource::<Output>().0, 11);
+
+        app.world_mut().resource_mut::<Source>().0 = 3;
+        app.step();
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+        assert_eq!(app.world().get::<Doubled>(target), Some(&Doubled(6)));
+        assert_eq!(app.world().resource::<Output>().0, 13);
+
+        // A change to either branch runs the whole fan-out.
+        app.world_mut().resource_mut::<Offset>().0 = 20;
+        app.step();
+        assert_eq!(runs.load(Ordering::Relaxed), 3);
+        assert_eq!(app.world().resource::<Output>().0, 23);
+    }
+
+    #[test]
+    fn map_input_transforms_the_input() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().insert_resource(Source(1));
+        app.spawn(Reaction::new(IntoReactiveSystem::map(
+            |_: In<Scope>, source: Res<Source>| source.0,
+            set_output.map_input(|value: u32| value + 100),
+        )));
+        app.step();
+        assert_eq!(app.world().resource::<Output>().0, 101);
+
+        app.world_mut().resource_mut::<Source>().0 = 5;
+        app.step();
+        assert_eq!(app.world().resource::<Output>().0, 105);
+    }
+
+    #[derive(Resource)]
+    struct Message(Result<u32, String>);
+
+    #[test]
+    fn map_err_only_transforms_errors() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().insert_resource(Source(5));
+        app.spawn(Reaction::new(IntoReactiveSystem::map(
+            (|_: In<Scope>, source: Res<Source>| source.0.checked_sub(4).ok_or(source.0))
+                .map_err(|value: u32| format!("{value} is too small")),
+            |scope: In<Scope<Result<u32, String>>>, mut commands: Commands| {
+                commands.insert_resource(Message(scope.0.input));
+            },
+        )));
+        app.step();
+        assert_eq!(app.world().resource::<Message>().0, Ok(1));
+
+        app.world_mut().resource_mut::<Source>().0 = 3;
+        app.step();
+        assert_eq!(
+            app.world().resource::<Message>().0,
+            Err(String::from("3 is too small"))
+        );
+    }
+
+    #[derive(Default, Resource)]
+    struct Produced(Vec<(Entity, i32)>);
+
+    #[test]
+    fn with_entity_pairs_outputs_with_their_target() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Produced>();
+        let a = app.spawn_target(Health(1));
+        let b = app.spawn_target(Health(2));
+        let mut reaction = Reaction::new(IntoReactiveSystem::map(
+            (|scope: In<Scope>, query: Query<&Health>| query.get(scope.entity).unwrap().0)
+                .with_entity(),
+            |scope: In<Scope<(Entity, i32)>>, mut commands: Commands| {
+                let produced = scope.0.input;
+                commands.add(move |world: &mut World| {
+                    world.resource_mut::<Produced>().0.push(produced);
+                });
+            },
+        ));
+        reaction.add_target(a).add_target(b);
+        app.spawn(reaction);
+        app.step();
+        let mut produced = mem::take(&mut app.world_mut().resource_mut::<Produced>().0);
+        produced.sort();
+        assert_eq!(produced, vec![(a, 1), (b, 2)]);
+
+        app.world_mut().get_mut::<Health>(b).unwrap().0 = 5;
+        app.step();
+        let produced = mem::take(&mut app.world_mut().resource_mut::<Produced>().0);
+        assert_eq!(produced, vec![(b, 5)]);
+    }
+
+    #[test]
+    fn boxed_systems_of_different_types_react_independently() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().insert_resource(Source(1));
+        app.world_mut().insert_resource(Offset(10));
+        let target = app.spawn_target(());
+        let systems: Vec<BoxedReactiveSystem> = vec![
+            box_reactive(
+                |_: In<Scope>, source: Res<Source>, mut commands: Commands| {
+                    commands.insert_resource(Output(source.0));
+                },
+            ),
+            box_reactive(
+                |scope: In<Scope>, offset: Res<Offset>, mut commands: Commands| {
+                    commands.entity(scope.entity).insert(Doubled(offset.0 * 2));
+                },
+            ),
+        ];
+        let reactions: Vec<_> = systems
+            .into_iter()
+            .map(|system| {
+                let mut reaction = Reaction::new(system);
+                reaction.add_target(target);
+                app.spawn(reaction)
+            })
+            .collect();
+        app.step();
+        assert_eq!(app.world().resource::<Output>().0, 1);
+        assert_eq!(app.world().get::<Doubled>(target), Some(&Doubled(20)));
+
+        app.world_mut().resource_mut::<Source>().0 = 2;
+        app.step();
+        assert_eq!(app.world().resource::<Output>().0, 2);
+        assert_eq!(app.runs(reactions[0]), 2);
+        assert_eq!(app.runs(reactions[1]), 1);
+    }
+
+    #[test]
+    fn dirty_when_forces_runs_while_the_predicate_holds() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().insert_resource(Source(0));
+        app.world_mut().insert_resource(Offset(0));
+        let reaction = app.spawn(Reaction::new(
+            (|_: In<Scope>, _: Res<Source>| {})
+                .dirty_when(|world: &World| world.resource::<Offset>().0 > 0),
+        ));
+        app.step();
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+
+        // `Offset` isn't a parameter, but the predicate runs the reaction every frame it holds.
+        app.world_mut().resource_mut::<Offset>().0 = 1;
+        app.step_n(2);
+        assert_eq!(app.runs(reaction), 3);
+
+        app.world_mut().resource_mut::<Offset>().0 = 0;
+        app.step();
+        assert_eq!(app.runs(reaction), 3);
+
+        app.world_mut().resource_mut::<Source>().0 = 1;
+        app.step();
+        assert_eq!(app.runs(reaction), 4);
+    }
+
+    #[test]
+    fn chain_runs_both_systems_with_the_same_input() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().insert_resource(Source(1));
+        app.world_mut().insert_resource(Offset(10));
+        let target = app.spawn_target(());
+        let mut reaction = Reaction::new(IntoReactiveSystem::map(
+            |_: In<Scope>, source: Res<Source>| source.0,
+            IntoReactiveSystem::chain(
+                |scope: In<Scope<u32>>, mut commands: Commands| {
+                    commands.insert_resource(Output(scope.input));
+                },
+                |scope: In<Scope<u32>>, offset: Res<Offset>, mut commands: Commands| {
+                    commands
+                        .entity(scope.entity)
+                        .insert(Doubled(scope.input + offset.0));
+                },
+            ),
+        ));
+        reaction.add_target(target);
+        app.spawn(reaction);
+        app.step();
+        assert_eq!(app.world().resource::<Output>().0, 1);
+        assert_eq!(app.world().get::<Doubled>(target), Some(&Doubled(11)));
+
+        // A change to the second system also runs the first.
+        app.world_mut().remove_resource::<Output>();
+        app.world_mut().resource_mut::<Offset>().0 = 20;
+        app.step();
+        assert_eq!(app.world().resource::<Output>().0, 1);
+        assert_eq!(app.world().get::<Doubled>(target), Some(&Doubled(21)));
+
+        app.world_mut().resource_mut::<Source>().0 = 2;
+        app.step();
+        assert_eq!(app.world().resource::<Output>().0, 2);
+        assert_eq!(app.world().get::<Doubled>(target), Some(&Doubled(22)));
+    }
+}
@@ -1,29 +1,109 @@
-use bevy_app::{App, Plugin, PostUpdate};
-use bevy_ecs::schedule::ScheduleLabel;
+use bevy_app::{App, AppExit, First, FixedUpdate, Last, Plugin, PostUpdate};
+#[cfg(feature = "diagnostics")]
+use bevy_diagnostic::{Diagnostic, RegisterDiagnostic};
+use bevy_ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy_ecs::{component::ComponentId, entity::EntityHashMap, query::FilteredAccessSet};
 use bevy_ecs::{prelude::*, world::DeferredWorld};
+use bevy_tasks::{ComputeTaskPool, TaskPool};
+use bevy_utils::{
+    tracing::{error, warn},
+    HashMap,
+};
+use std::mem;
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
 
+pub use bevy_mod_reaction_macros::{ReactiveQueryData, ReactiveSystemParam};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use bevy_ecs;
+}
+
+#[cfg(feature = "bevy_asset")]
+mod asset;
+#[cfg(feature = "bevy_asset")]
+pub use self::asset::{ReactiveAssetEvents, ReactiveAssetEventsState};
+
+mod changed_any;
+pub use self::changed_any::{ChangedAny, ChangedAnyState, ComponentTuple};
+
+mod delta;
+pub use self::delta::{Accumulate, AccumulatedDelta, AccumulatedDeltaState};
+
+mod diagnostics;
+pub use self::diagnostics::ReactionDiagnostics;
+
+mod diff;
+pub use self::diff::DiffBundle;
+
+mod error;
+pub use self::error::{ReactionError, ReactionErrorHandler};
+
+mod hashed;
+pub use self::hashed::{Hashed, HashedState};
+
+mod hierarchy;
+pub use self::hierarchy::{
+    HierarchyState, ReactiveChildren, ReactiveDescendants, ReactiveDescendantsState, ReactiveParent,
+};
+
 mod into_system;
-pub use self::into_system::{IntoReactiveSystem, Map};
+#[cfg(feature = "time")]
+pub use self::into_system::Every;
+pub use self::into_system::{
+    box_reactive, combine_latest, AndThen, AndThenOk, AsyncReaction, Chain, CombineLatest,
+    DirtyWhen, FanOut, FromWorldSystem, IntoLatestSources, IntoReactiveSystem, LatestSources, Map,
+    MapErr, MapInput, MapOpt, OnChanged, OnRemoved, RunOnce, Take, WithEntity, WithInput,
+};
+
+mod message;
+pub use self::message::{ReactionMessages, ReactionReceiver, ReactionSender};
 
 mod query_data;
-pub use self::query_data::ReactiveQueryData;
+pub use self::query_data::{
+    ComponentQueryState, FilterQueryState, HasQueryState, OptionQueryState, ReactiveQueryData,
+    RefQueryState,
+};
 
 mod reaction;
-pub use self::reaction::Reaction;
+pub use self::reaction::{GroupKey, Reaction, ReactionHandle, SharedReaction};
+
+mod registry;
+pub use self::registry::ReactionRegistry;
+
+mod resources;
+pub use self::resources::{ReactiveResources, ResourceTuple};
+
+mod selector;
+pub use self::selector::{Selector, SelectorId, Selectors};
 
 mod system;
-pub use self::system::ReactiveSystem;
+pub use self::system::{BoxedReactiveSystem, ReactiveSystem};
 
 mod system_fn;
 pub use self::system_fn::{FunctionReactiveSystem, ReactiveSystemParamFunction};
 
 mod system_param;
-pub use self::system_param::{ReactiveQuery, ReactiveQueryState, ReactiveSystemParam};
+pub use self::system_param::{
+    changed, init_state, Mutated, Peek, ReactiveParallelCommands, ReactiveQuery,
+    ReactiveQueryCache, ReactiveQueryError, ReactiveQueryMut, ReactiveQueryMutState,
+    ReactiveQueryState, ReactiveSystemParam, ReactiveWorld, TrackingPolicy,
+};
 
+#[cfg(any(test, feature = "test-util"))]
+pub mod test;
+
+mod wake;
+pub use self::wake::{ReactionSubscriptions, Subscribed};
+
+/// Input of a reactive system.
 pub struct Scope<T = ()> {
+    /// The target the reaction is running for,
+    /// or the reaction's own entity if it has no targets.
     pub entity: Entity,
+
+    /// The input passed to the reactive system.
     pub input: T,
 }
 
@@ -41,10 +121,22 @@ impl<T> DerefMut for Scope<T> {
     }
 }
 
-type PluginFn = Arc<dyn Fn(&mut App) + Send + Sync>;
+type PluginFn = Arc<dyn Fn(&mut App, &ReactionPlugin) + Send + Sync>;
+
+/// System used to run the reactions of each label.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Driver {
+    Serial,
+    Parallel,
+    Observed,
+    Settle { max_passes: usize, max_runs: usize },
+}
 
 pub struct ReactionPlugin {
     fns: Vec<PluginFn>,
+    schedule: Option<InternedScheduleLabel>,
+    driver: Driver,
+    tracking_policy: TrackingPolicy,
 }
 
 impl ReactionPlugin {
@@ -55,15 +147,96 @@ impl ReactionPlugin {
     }
 
     pub fn empty() -> Self {
-        Self { fns: Vec::new() }
+        Self {
+            fns: Vec::new(),
+            schedule: None,
+            driver: Driver::Serial,
+            tracking_policy: TrackingPolicy::default(),
+        }
+    }
+
+    /// Run reactions in parallel with [`react_parallel`] when their access doesn't conflict.
+    pub fn parallel(mut self) -> Self {
+        self.driver = Driver::Parallel;
+        self
+    }
+
+    /// Run reactions with [`react_observed`], so subscribed reactions only run once woken up.
+    pub fn observed(mut self) -> Self {
+        self.driver = Driver::Observed;
+        self
+    }
+
+    /// Run reactions with [`react_settle`], repeating each pass until no reaction changes,
+    /// so cascades of reactions settle in the same frame.
+    ///
+    /// At most 16 passes are run per frame, so feedback loops can't hang the app.
+    /// See [`ReactionPlugin::max_settle_runs`] to find the reactions in a loop.
+    pub fn settle(self) -> Self {
+        self.max_settle_runs(8)
+    }
+
+    /// Settle reactions like [`ReactionPlugin::settle`], stopping early with an error
+    /// once a single reaction runs more than `max_runs` times in a frame.
+    pub fn max_settle_runs(mut self, max_runs: usize) -> Self {
+        self.driver = Driver::Settle {
+            max_passes: 16,
+            max_runs,
+        };
+        self
+    }
+
+    /// Also run reactions with the [`FixedUpdate`] label, created with [`Reaction::fixed`].
+    ///
+    /// These run once per fixed timestep, so a change is detected by the first step after it,
+    /// even when several steps run in the same frame.
+    pub fn fixed_update(self) -> Self {
+        self.with_label(FixedUpdate)
+    }
+
+    /// Set the [`TrackingPolicy`] for the entities tracked by each [`ReactiveQuery`].
+    pub fn tracking_policy(mut self, policy: TrackingPolicy) -> Self {
+        self.tracking_policy = policy;
+        self
+    }
+
+    /// Run all reactions in `schedule`, instead of the schedule of their label.
+    pub fn in_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.schedule = Some(schedule.intern());
+        self
+    }
+
+    /// Run reactions with the label `L` in the schedule `label`.
+    pub fn with_label<L>(mut self, label: L) -> Self
+    where
+        L: ScheduleLabel + Clone,
+    {
+        self.add_label(label);
+        self
     }
 
     pub fn add_label<L>(&mut self, label: L) -> &mut Self
     where
         L: ScheduleLabel + Clone,
     {
-        let f = Arc::new(move |app: &mut App| {
-            app.add_systems(label.clone(), react::<L>);
+        let f = Arc::new(move |app: &mut App, plugin: &ReactionPlugin| {
+            let schedule = plugin.schedule.unwrap_or_else(|| label.intern());
+            let interned = label.intern();
+            app.add_systems(schedule, move |world: &mut World| {
+                react_shared(world, interned);
+            });
+            match plugin.driver {
+                Driver::Serial => app.add_systems(schedule, react::<L>),
+                Driver::Parallel => app.add_systems(schedule, react_parallel::<L>),
+                Driver::Observed => app.add_systems(schedule, react_observed::<L>),
+                Driver::Settle {
+                    max_passes,
+                    max_runs,
+                } => app.add_systems(schedule, move |world: &mut World| {
+                    react_settle::<L>(world, max_passes, max_runs)
+                }),
+            };
+            app.add_systems(Last, react_exit::<L>);
         });
         self.fns.push(f);
         self
@@ -78,21 +251,544 @@ impl Default for ReactionPlugin {
 
 impl Plugin for ReactionPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<ReactionDiagnostics>()
+            .init_resource::<ReactionRegistry>()
+            .insert_resource(self.tracking_policy)
+            .add_systems(First, diagnostics::reset_diagnostics);
+
+        #[cfg(feature = "diagnostics")]
+        app.register_diagnostic(Diagnostic::new(ReactionDiagnostics::POLLED))
+            .register_diagnostic(Diagnostic::new(ReactionDiagnostics::CHANGED))
+            .register_diagnostic(Diagnostic::new(ReactionDiagnostics::RAN))
+            .add_systems(Last, diagnostics::measure_diagnostics);
+
         for f in &self.fns {
-            f(app);
+            f(app, self);
         }
     }
 }
 
-pub fn react<L: ScheduleLabel>(
-    world: DeferredWorld,
-    reaction_query: Query<(Entity, &Reaction<L>)>,
+/// Reactions with the label `L` that aren't run by [`react_shared`].
+type ReactionQuery<'w, 's, L, F = ()> =
+    Query<'w, 's, (Entity, &'static Reaction<L>), (Without<SharedReaction>, F)>;
+
+/// Run each reaction with the label `L`, one at a time.
+pub fn react<L: ScheduleLabel>(mut world: DeferredWorld, reaction_query: ReactionQuery<L>) {
+    let mut diagnostics = ReactionDiagnostics::default();
+    let mut groups = HashMap::new();
+    for (entity, reaction) in sorted_reactions(&reaction_query) {
+        diagnostics += run_grouped(world.reborrow(), entity, reaction, &mut groups);
+    }
+    diagnostics::record(&mut world, diagnostics);
+}
+
+/// Run the reactions with the label `L` in parallel.
+///
+/// Reactions are grouped into batches with compatible world access,
+/// and each batch is run in parallel on the [`ComputeTaskPool`].
+pub fn react_parallel<L: ScheduleLabel>(
+    mut world: DeferredWorld,
+    reaction_query: ReactionQuery<L>,
 ) {
+    let mut batches: Vec<(FilteredAccessSet<ComponentId>, Vec<_>)> = Vec::new();
+    let mut priority = None;
+    let mut start = 0;
+    for (entity, reaction) in sorted_reactions(&reaction_query) {
+        // Only batch reactions with the same priority, so batches run in order.
+        if priority != Some(reaction.priority()) {
+            priority = Some(reaction.priority());
+            start = batches.len();
+        }
+
+        let access = reaction.access();
+        if let Some((batch_access, batch)) = batches[start..]
+            .iter_mut()
+            .find(|(batch_access, _)| batch_access.is_compatible(&access))
+        {
+            batch_access.extend(access);
+            batch.push((entity, reaction));
+        } else {
+            batches.push((access, vec![(entity, reaction)]));
+        }
+    }
+
+    let mut diagnostics = ReactionDiagnostics::default();
     let world_cell = world.as_unsafe_world_cell_readonly();
-    reaction_query
-        .par_iter()
-        .for_each(move |(entity, reaction)| {
-            let mut world = unsafe { world_cell.into_deferred() };
-            reaction.run(world.reborrow(), entity);
+    let task_pool = ComputeTaskPool::get_or_init(TaskPool::default);
+    for (_, batch) in batches {
+        let chunk_size = batch.len().div_ceil(task_pool.thread_num()).max(1);
+        let chunk_diagnostics = task_pool.scope(|scope| {
+            for chunk in batch.chunks(chunk_size) {
+                scope.spawn(async move {
+                    let mut diagnostics = ReactionDiagnostics::default();
+                    for &(entity, reaction) in chunk {
+                        // SAFETY: Reactions in the same batch have compatible access.
+                        let world = unsafe { world_cell.into_deferred() };
+                        diagnostics += reaction.run_with_diagnostics(world, entity);
+                    }
+                    diagnostics
+                });
+            }
+        });
+        for chunk_diagnostics in chunk_diagnostics {
+            diagnostics += chunk_diagnostics;
+        }
+    }
+    diagnostics::record(&mut world, diagnostics);
+}
+
+/// Run the reactions with the label `L` that were woken up by [`ReactionSubscriptions`],
+/// and poll the rest like [`react`].
+pub fn react_observed<L: ScheduleLabel>(
+    mut world: DeferredWorld,
+    reaction_query: ReactionQuery<L, Without<Subscribed>>,
+    subscribed_query: ReactionQuery<L, With<Subscribed>>,
+) {
+    let mut woken = world
+        .get_resource_mut::<ReactionSubscriptions>()
+        .map(|mut subscriptions| mem::take(&mut subscriptions.woken))
+        .unwrap_or_default();
+
+    let mut reactions: Vec<_> = reaction_query
+        .iter()
+        .chain(
+            woken
+                .iter()
+                .filter_map(|entity| subscribed_query.get(*entity).ok()),
+        )
+        .collect();
+    reactions.sort_by_key(|(_, reaction)| reaction.priority());
+
+    let mut diagnostics = ReactionDiagnostics::default();
+    let mut groups = HashMap::new();
+    for (entity, reaction) in reactions {
+        woken.remove(&entity);
+        diagnostics += run_grouped(world.reborrow(), entity, reaction, &mut groups);
+    }
+    diagnostics::record(&mut world, diagnostics);
+
+    // Keep reactions with other labels woken up.
+    if let Some(mut subscriptions) = world.get_resource_mut::<ReactionSubscriptions>() {
+        subscriptions.woken.extend(woken);
+    }
+}
+
+/// Run each reaction with the label `L` like [`react`], applying commands after each pass
+/// and repeating until no reaction changes, or `max_passes` have run.
+///
+/// Each pass only sees changes made since the previous pass started.
+/// If a reaction runs more than `max_runs` times, it's likely in a feedback loop,
+/// so an error is logged and no more passes are run.
+pub fn react_settle<L: ScheduleLabel + Clone>(
+    world: &mut World,
+    max_passes: usize,
+    max_runs: usize,
+) {
+    let mut diagnostics = ReactionDiagnostics::default();
+    let mut last_change_tick = world.last_change_tick();
+    let mut runs = EntityHashMap::default();
+    let mut is_stopped = false;
+    for _ in 0..max_passes {
+        let reactions: Vec<_> = world
+            .query_filtered::<(Entity, &Reaction<L>), Without<SharedReaction>>()
+            .iter(world)
+            .map(|(entity, reaction)| (entity, reaction.clone()))
+            .collect();
+        let mut reactions: Vec<_> = reactions.iter().map(|(entity, r)| (*entity, r)).collect();
+        reactions.sort_by_key(|(_, reaction)| reaction.priority());
+
+        let this_run = world.increment_change_tick();
+        let (pass, is_looping) = world.last_change_tick_scope(last_change_tick, |world| {
+            let mut world = DeferredWorld::from(world);
+            let mut pass = ReactionDiagnostics::default();
+            let mut groups = HashMap::new();
+            for (entity, reaction) in reactions {
+                let reaction_diagnostics =
+                    run_grouped(world.reborrow(), entity, reaction, &mut groups);
+                pass += reaction_diagnostics;
+
+                if reaction_diagnostics.changed > 0 {
+                    let count = runs.entry(entity).or_insert(0);
+                    *count += 1;
+                    if *count > max_runs {
+                        error!(
+                            "Reaction {} ({entity}) ran {count} times while settling, \
+                            stopping to break a feedback loop",
+                            reaction.name().as_deref().unwrap_or("<unnamed>")
+                        );
+                        return (pass, true);
+                    }
+                }
+            }
+            (pass, false)
+        });
+        world.flush();
+        last_change_tick = this_run;
+
+        diagnostics += pass;
+        // Loops were already logged, so stop without warning about the pass limit.
+        if is_looping || pass.changed == 0 {
+            is_stopped = true;
+            break;
+        }
+    }
+
+    if !is_stopped {
+        warn!("Reactions didn't settle after {max_passes} passes");
+    }
+    diagnostics::record(&mut world.into(), diagnostics);
+}
+
+/// Run the reactions for `label` that also run in other schedules, created with
+/// [`Reaction::also_in`], one at a time.
+///
+/// Each reaction only sees changes made since it was last polled in any of its schedules,
+/// so a change is handled by the first schedule that runs after it.
+pub fn react_shared(world: &mut World, label: InternedScheduleLabel) {
+    let mut reactions: Vec<_> = world
+        .query::<(Entity, &SharedReaction)>()
+        .iter(world)
+        .filter(|(_, shared)| shared.runs_in(label))
+        .map(|(entity, shared)| (entity, shared.reaction().clone()))
+        .collect();
+    if reactions.is_empty() {
+        return;
+    }
+    reactions.sort_by_key(|(_, reaction)| reaction.priority());
+
+    let mut diagnostics = ReactionDiagnostics::default();
+    for (entity, reaction) in reactions {
+        let last_change_tick = reaction
+            .last_polled()
+            .unwrap_or_else(|| world.last_change_tick());
+        diagnostics += world.last_change_tick_scope(last_change_tick, |world| {
+            reaction.run_with_diagnostics(world.into(), entity)
         });
+
+        // Changes made by this reaction are older than its next poll, like in other drivers.
+        reaction.set_last_polled(world.increment_change_tick());
+    }
+    world.flush();
+    diagnostics::record(&mut world.into(), diagnostics);
+}
+
+/// Run `reaction`, sharing change detection with the reactions in its group that already ran.
+fn run_grouped<L: ScheduleLabel>(
+    world: DeferredWorld,
+    entity: Entity,
+    reaction: &Reaction<L>,
+    groups: &mut HashMap<GroupKey, bool>,
+) -> ReactionDiagnostics {
+    let Some(group) = reaction.group() else {
+        return reaction.run_with_diagnostics(world, entity);
+    };
+
+    if let Some(is_changed) = groups.get(&group) {
+        return reaction.run_in_group(world, entity, Some(*is_changed));
+    }
+
+    let diagnostics = reaction.run_with_diagnostics(world, entity);
+    groups.insert(group, diagnostics.changed > 0);
+    diagnostics
+}
+
+/// Collect the reactions in `reaction_query`, sorted by their priority.
+fn sorted_reactions<'a, L: ScheduleLabel>(
+    reaction_query: &'a ReactionQuery<L>,
+) -> Vec<(Entity, &'a Reaction<L>)> {
+    let mut reactions: Vec<_> = reaction_query.iter().collect();
+    reactions.sort_by_key(|(_, reaction)| reaction.priority());
+    reactions
+}
+
+/// Run reactions created with [`Reaction::on_exit`] once an [`AppExit`] event is sent.
+pub fn react_exit<L: ScheduleLabel + Clone>(world: &mut World) {
+    let is_exiting = world
+        .get_resource::<Events<AppExit>>()
+        .is_some_and(|events| !events.is_empty());
+    if !is_exiting {
+        return;
+    }
+
+    let reactions: Vec<_> = world
+        .query::<(Entity, &Reaction<L>)>()
+        .iter(world)
+        .map(|(entity, reaction)| (entity, reaction.clone()))
+        .collect();
+
+    for (entity, reaction) in reactions {
+        reaction.run_exit(world.into(), entity);
+    }
+    world.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::ReactionTestApp;
+    use bevy_app::{PreUpdate, Update};
+
+    #[derive(Default, Resource)]
+    struct Trigger(u32);
+
+    #[derive(Component)]
+    struct Spawned(u32);
+
+    #[test]
+    fn uninitialized_reactions_run_alone() {
+        let reaction = Reaction::new(|_: In<Scope>, _: Res<Trigger>| {});
+        let other = Reaction::new(|_: In<Scope>, _: Res<Trigger>| {});
+        assert!(!reaction.access().is_compatible(&other.access()));
+    }
+
+    #[test]
+    fn parallel_reactions_with_commands() {
+        let mut app = ReactionTestApp::with_plugin(ReactionPlugin::new().parallel());
+        app.world_mut().init_resource::<Trigger>();
+        for id in 0..2 {
+            app.spawn(Reaction::new(
+                move |_: In<Scope>, trigger: Res<Trigger>, mut commands: Commands| {
+                    commands.spawn(Spawned(id + trigger.0 * 2));
+                },
+            ));
+        }
+
+        app.step();
+        for _ in 0..4 {
+            app.world_mut().resource_mut::<Trigger>().0 += 1;
+            app.step();
+        }
+
+        let mut spawned: Vec<_> = app
+            .world_mut()
+            .query::<&Spawned>()
+            .iter(app.world())
+            .map(|spawned| spawned.0)
+            .collect();
+        spawned.sort_unstable();
+        assert_eq!(spawned, (0..10).collect::<Vec<_>>());
+    }
+
+    #[derive(Default, Resource)]
+    struct Doubled(u32);
+
+    #[derive(Default, Resource)]
+    struct Quadrupled(u32);
+
+    fn cascade(app: &mut ReactionTestApp) -> (Entity, Entity) {
+        app.world_mut().init_resource::<Trigger>();
+        app.world_mut().init_resource::<Doubled>();
+        app.world_mut().init_resource::<Quadrupled>();
+
+        // The downstream reaction runs first, so it only sees the change in a later pass.
+        let downstream = app.spawn(
+            Reaction::new(
+                |_: In<Scope>, doubled: Res<Doubled>, mut commands: Commands| {
+                    commands.insert_resource(Quadrupled(doubled.0 * 2));
+                },
+            )
+            .with_priority(0),
+        );
+        let upstream = app.spawn(
+            Reaction::new(
+                |_: In<Scope>, trigger: Res<Trigger>, mut commands: Commands| {
+                    commands.insert_resource(Doubled(trigger.0 * 2));
+                },
+            )
+            .with_priority(1),
+        );
+        (upstream, downstream)
+    }
+
+    #[test]
+    fn settle_runs_cascades_in_one_frame() {
+        let mut app = ReactionTestApp::with_plugin(ReactionPlugin::new().settle());
+        let (upstream, downstream) = cascade(&mut app);
+        app.step();
+
+        app.world_mut().resource_mut::<Trigger>().0 = 3;
+        app.step();
+        assert_eq!(app.world().resource::<Quadrupled>().0, 12);
+        let runs = (app.runs(upstream), app.runs(downstream));
+
+        // Settled, so nothing runs again.
+        app.step();
+        assert_eq!((app.runs(upstream), app.runs(downstream)), runs);
+    }
+
+    #[derive(Default, Resource)]
+    struct Ping(u32);
+
+    #[derive(Default, Resource)]
+    struct Pong(u32);
+
+    fn feedback_loop(app: &mut ReactionTestApp) -> (Entity, Entity) {
+        app.world_mut().init_resource::<Ping>();
+        app.world_mut().init_resource::<Pong>();
+        let ping = app.spawn(Reaction::new(
+            |_: In<Scope>, pong: Res<Pong>, mut commands: Commands| {
+                commands.insert_resource(Ping(pong.0 + 1));
+            },
+        ));
+        let pong = app.spawn(Reaction::new(
+            |_: In<Scope>, ping: Res<Ping>, mut commands: Commands| {
+                commands.insert_resource(Pong(ping.0 + 1));
+            },
+        ));
+        (ping, pong)
+    }
+
+    #[test]
+    fn settle_breaks_feedback_loops() {
+        let mut app = ReactionTestApp::with_plugin(ReactionPlugin::new().max_settle_runs(3));
+        let (ping, pong) = feedback_loop(&mut app);
+
+        app.step();
+        assert!(app.runs(ping) > 1);
+        assert!(app.runs(ping) <= 4);
+        assert!(app.runs(pong) <= 4);
+
+        // Changes from the broken loop aren't carried over to the next frame.
+        let runs = (app.runs(ping), app.runs(pong));
+        app.step();
+        assert_eq!((app.runs(ping), app.runs(pong)), runs);
+    }
+
+    #[test]
+    fn settle_stops_after_max_passes() {
+        let mut app = ReactionTestApp::with_plugin(ReactionPlugin::new().max_settle_runs(100));
+        let (ping, pong) = feedback_loop(&mut app);
+
+        app.step();
+        assert_eq!(app.runs(ping), 16);
+        assert_eq!(app.runs(pong), 16);
+    }
+
+    #[derive(Resource)]
+    struct Seen(u32);
+
+    #[derive(Default, Resource)]
+    struct Copied(Option<u32>);
+
+    #[test]
+    fn in_schedule_runs_reactions_in_schedule() {
+        let mut app = ReactionTestApp::with_plugin(ReactionPlugin::new().in_schedule(PreUpdate));
+        app.world_mut().init_resource::<Trigger>();
+        app.world_mut().init_resource::<Copied>();
+        app.app_mut().add_systems(
+            Update,
+            |seen: Option<Res<Seen>>, mut copied: ResMut<Copied>| {
+                copied.0 = seen.map(|seen| seen.0);
+            },
+        );
+        app.spawn(Reaction::new(
+            |_: In<Scope>, trigger: Res<Trigger>, mut commands: Commands| {
+                commands.insert_resource(Seen(trigger.0));
+            },
+        ));
+        app.step_n(2);
+
+        // Reactions ran before `Update` in the same frame.
+        app.world_mut().resource_mut::<Trigger>().0 = 1;
+        app.step();
+        assert_eq!(app.world().resource::<Copied>().0, Some(1));
+    }
+
+    #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+    struct Custom;
+
+    #[derive(Default, Resource)]
+    struct RunCustom(bool);
+
+    #[test]
+    fn with_label_runs_reactions_in_their_schedule() {
+        let mut app = ReactionTestApp::with_plugin(ReactionPlugin::new().with_label(Custom));
+        app.world_mut().init_resource::<Trigger>();
+        app.world_mut().init_resource::<RunCustom>();
+        app.app_mut()
+            .init_schedule(Custom)
+            .add_systems(Update, |world: &mut World| {
+                if world.resource::<RunCustom>().0 {
+                    world.run_schedule(Custom);
+                }
+            });
+        let custom = app.spawn(Reaction::from_label(
+            Custom,
+            |_: In<Scope>, _: Res<Trigger>| {},
+        ));
+        let default = app.spawn(Reaction::new(|_: In<Scope>, _: Res<Trigger>| {}));
+
+        app.step();
+        app.world_mut().resource_mut::<Trigger>().0 += 1;
+        app.step();
+        assert_eq!(app.runs(custom), 0);
+        assert_eq!(app.runs(default), 2);
+
+        app.world_mut().resource_mut::<RunCustom>().0 = true;
+        app.world_mut().resource_mut::<Trigger>().0 += 1;
+        app.step();
+        assert_eq!(app.runs(custom), 1);
+        assert_eq!(app.runs(default), 3);
+    }
+
+    #[derive(Default, Resource)]
+    struct Order(Vec<i32>);
+
+    fn run_in_priority_order(plugin: ReactionPlugin) {
+        let mut app = ReactionTestApp::with_plugin(plugin);
+        app.world_mut().init_resource::<Trigger>();
+        app.world_mut().init_resource::<Order>();
+        for priority in [2, -1, 1, 0] {
+            app.spawn(
+                Reaction::new(
+                    move |_: In<Scope>, _: Res<Trigger>, mut commands: Commands| {
+                        commands.add(move |world: &mut World| {
+                            world.resource_mut::<Order>().0.push(priority);
+                        });
+                    },
+                )
+                .with_priority(priority),
+            );
+        }
+        app.step();
+        app.world_mut().resource_mut::<Order>().0.clear();
+
+        app.world_mut().resource_mut::<Trigger>().0 += 1;
+        app.step();
+        assert_eq!(app.world().resource::<Order>().0, vec![-1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn reactions_run_in_priority_order() {
+        run_in_priority_order(ReactionPlugin::new());
+        run_in_priority_order(ReactionPlugin::new().parallel());
+        run_in_priority_order(ReactionPlugin::new().settle());
+    }
+
+    #[derive(Component)]
+    struct Alarm(u32);
+
+    #[test]
+    fn observed_reactions_idle_until_woken() {
+        let mut app = ReactionTestApp::with_plugin(ReactionPlugin::new().observed());
+        let alarm = app.spawn_target(Alarm(0));
+        let reaction =
+            app.spawn(Reaction::new(|_: In<Scope>, _: Query<&Alarm>| {}).wake_on::<Alarm>());
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+
+        // Mutating an alarm in place doesn't wake the reaction, so it isn't even polled.
+        app.world_mut().get_mut::<Alarm>(alarm).unwrap().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+        assert_eq!(app.world().resource::<ReactionDiagnostics>().polled, 0);
+
+        app.spawn_target(Alarm(0));
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+        assert_eq!(app.world().resource::<ReactionDiagnostics>().polled, 0);
+    }
 }
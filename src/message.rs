@@ -0,0 +1,374 @@
+use crate::{
+    system_param::{add_access, add_deferred_access},
+    ReactiveSystemParam,
+};
+use bevy_ecs::{
+    component::{ComponentId, Tick},
+    prelude::*,
+    query::{FilteredAccess, FilteredAccessSet},
+    system::{SystemMeta, SystemParam},
+    world::{unsafe_world_cell::UnsafeWorldCell, DeferredWorld},
+};
+use std::{
+    collections::VecDeque,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Weak,
+    },
+};
+
+/// Queue of messages sent between reactions.
+///
+/// Messages are kept until every [`ReactionReceiver`] has read them.
+/// The cursor of a receiver is released once its reaction is despawned or reset.
+#[derive(Resource)]
+pub struct ReactionMessages<T> {
+    messages: VecDeque<T>,
+    head: usize,
+    cursors: Vec<Weak<AtomicUsize>>,
+}
+
+impl<T> Default for ReactionMessages<T> {
+    fn default() -> Self {
+        Self {
+            messages: VecDeque::new(),
+            head: 0,
+            cursors: Vec::new(),
+        }
+    }
+}
+
+impl<T> ReactionMessages<T> {
+    /// Push a new message to the queue.
+    pub fn send(&mut self, message: T) {
+        self.messages.push_back(message);
+        self.trim();
+    }
+
+    /// Returns the number of messages currently stored.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns `true` if there are no stored messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Add a cursor for a new receiver, starting at the oldest stored message.
+    fn add_cursor(&mut self) -> Arc<AtomicUsize> {
+        let cursor = Arc::new(AtomicUsize::new(self.head));
+        self.cursors.push(Arc::downgrade(&cursor));
+        cursor
+    }
+
+    fn end(&self) -> usize {
+        self.head + self.messages.len()
+    }
+
+    fn trim(&mut self) {
+        self.cursors.retain(|cursor| cursor.strong_count() > 0);
+        let min = self
+            .cursors
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|cursor| cursor.load(Ordering::Acquire))
+            .min()
+            .unwrap_or(self.end());
+
+        while self.head < min {
+            self.messages.pop_front();
+            self.head += 1;
+        }
+    }
+}
+
+/// Reactive system parameter to send messages to [`ReactionReceiver`]s.
+///
+/// Messages are queued as commands and become visible once they are applied.
+pub struct ReactionSender<'w, 's, T: Send + Sync + 'static> {
+    commands: Commands<'w, 's>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Send + Sync + 'static> ReactionSender<'_, '_, T> {
+    /// Send a message to every [`ReactionReceiver`] of this type.
+    pub fn send(&mut self, message: T) {
+        self.commands.add(move |world: &mut World| {
+            world
+                .get_resource_or_insert_with(ReactionMessages::<T>::default)
+                .send(message);
+        });
+    }
+}
+
+/// Outside of reactions, a [`ReactionSender`] queues its messages with the system's commands.
+unsafe impl<T: Send + Sync + 'static> SystemParam for ReactionSender<'_, '_, T> {
+    type State = <Commands<'static, 'static> as SystemParam>::State;
+
+    type Item<'world, 'state> = ReactionSender<'world, 'state, T>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        world.init_resource::<ReactionMessages<T>>();
+        Commands::init_state(world, system_meta)
+    }
+
+    fn apply(state: &mut Self::State, system_meta: &SystemMeta, world: &mut World) {
+        Commands::apply(state, system_meta, world);
+    }
+
+    fn queue(state: &mut Self::State, system_meta: &SystemMeta, world: DeferredWorld) {
+        Commands::queue(state, system_meta, world);
+    }
+
+    unsafe fn get_param<'world, 'state>(
+        state: &'state mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'world>,
+        change_tick: Tick,
+    ) -> Self::Item<'world, 'state> {
+        ReactionSender {
+            // SAFETY: Commands only read the entities of `world`.
+            commands: unsafe { Commands::get_param(state, system_meta, world, change_tick) },
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> ReactiveSystemParam for ReactionSender<'_, '_, T> {
+    type State = <Commands<'static, 'static> as ReactiveSystemParam>::State;
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        world.init_resource::<ReactionMessages<T>>();
+        Commands::init(world)
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) -> bool {
+        let _ = world;
+        let _ = state;
+
+        false
+    }
+
+    fn after_run(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) {
+        Commands::after_run(world, state);
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        add_deferred_access(world, access);
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        ReactionSender {
+            // SAFETY: Commands only read the entities of `world`.
+            commands: unsafe { Commands::get(world, state) },
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Reactive system parameter to receive messages from [`ReactionSender`]s.
+///
+/// Each receiver keeps its own cursor, so every receiver sees every message.
+/// Reactions with a receiver are changed while there are unread messages.
+pub struct ReactionReceiver<'w, T: Send + Sync + 'static> {
+    messages: &'w ReactionMessages<T>,
+    cursor: Arc<AtomicUsize>,
+}
+
+impl<'w, T: Send + Sync + 'static> ReactionReceiver<'w, T> {
+    /// Returns the number of unread messages.
+    pub fn len(&self) -> usize {
+        let cursor = self.cursor.load(Ordering::Acquire);
+        self.messages.end() - cursor
+    }
+
+    /// Returns `true` if there are no unread messages.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drain the unread messages, marking them as read.
+    pub fn read(&mut self) -> impl Iterator<Item = &'w T> {
+        let messages = self.messages;
+        let cursor = self.cursor.swap(messages.end(), Ordering::AcqRel);
+        messages.messages.iter().skip(cursor - messages.head)
+    }
+}
+
+/// Outside of reactions, a [`ReactionReceiver`] keeps its cursor with the system.
+unsafe impl<T: Send + Sync + 'static> SystemParam for ReactionReceiver<'_, T> {
+    type State = (
+        <Res<'static, ReactionMessages<T>> as SystemParam>::State,
+        Arc<AtomicUsize>,
+    );
+
+    type Item<'world, 'state> = ReactionReceiver<'world, T>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        let cursor = world
+            .get_resource_or_insert_with(ReactionMessages::<T>::default)
+            .add_cursor();
+        (
+            Res::<ReactionMessages<T>>::init_state(world, system_meta),
+            cursor,
+        )
+    }
+
+    unsafe fn get_param<'world, 'state>(
+        state: &'state mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'world>,
+        change_tick: Tick,
+    ) -> Self::Item<'world, 'state> {
+        let (messages, cursor) = state;
+
+        // SAFETY: The caller ensures `world` has read access to the messages.
+        let messages = unsafe { Res::get_param(messages, system_meta, world, change_tick) };
+        ReactionReceiver {
+            messages: Res::into_inner(messages),
+            cursor: cursor.clone(),
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> ReactiveSystemParam for ReactionReceiver<'_, T> {
+    type State = Arc<AtomicUsize>;
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        world
+            .get_resource_or_insert_with(ReactionMessages::<T>::default)
+            .add_cursor()
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) -> bool {
+        world
+            .get_resource::<ReactionMessages<T>>()
+            .is_some_and(|messages| state.load(Ordering::Acquire) < messages.end())
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        let id = world.init_resource::<ReactionMessages<T>>();
+
+        let mut filtered = FilteredAccess::default();
+        filtered.add_read(id);
+        add_access::<Self>(world, access, filtered);
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        ReactionReceiver {
+            // SAFETY: The messages are only read, as declared in `init_access`.
+            messages: unsafe { world.get_resource::<ReactionMessages<T>>() }
+                .expect("Messages are initialized with the receiver"),
+            cursor: state.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::ReactionTestApp, Reaction, Scope};
+    use std::sync::Mutex;
+
+    #[derive(Default, Resource)]
+    struct Trigger(u32);
+
+    fn receiver(app: &mut ReactionTestApp) -> (Entity, Arc<Mutex<Vec<u32>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let log = received.clone();
+        let entity = app.spawn(Reaction::new(
+            move |_: In<Scope>, mut receiver: ReactionReceiver<u32>| {
+                log.lock().unwrap().extend(receiver.read().copied());
+            },
+        ));
+        (entity, received)
+    }
+
+    fn send(app: &mut ReactionTestApp) {
+        app.world_mut().resource_mut::<Trigger>().0 += 1;
+        app.step();
+    }
+
+    #[test]
+    fn every_receiver_sees_every_message() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        app.spawn(Reaction::new(
+            |_: In<Scope>, trigger: Res<Trigger>, mut sender: ReactionSender<u32>| {
+                sender.send(trigger.0);
+            },
+        ));
+        let (_, a) = receiver(&mut app);
+        let (_, b) = receiver(&mut app);
+
+        app.step();
+        for _ in 0..3 {
+            send(&mut app);
+        }
+        app.step();
+
+        assert_eq!(*a.lock().unwrap(), [0, 1, 2, 3]);
+        assert_eq!(*b.lock().unwrap(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn despawned_receivers_release_their_cursor() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        app.spawn(Reaction::new(
+            |_: In<Scope>, trigger: Res<Trigger>, mut sender: ReactionSender<u32>| {
+                sender.send(trigger.0);
+            },
+        ));
+        let (_, a) = receiver(&mut app);
+        let (b, _) = receiver(&mut app);
+
+        app.step();
+        assert_eq!(
+            app.world()
+                .resource::<ReactionMessages<u32>>()
+                .cursors
+                .len(),
+            2
+        );
+
+        app.world_mut().despawn(b);
+        for _ in 0..3 {
+            send(&mut app);
+        }
+        app.step();
+
+        let messages = app.world().resource::<ReactionMessages<u32>>();
+        assert_eq!(messages.cursors.len(), 1);
+        assert!(messages.len() <= 1);
+        assert_eq!(*a.lock().unwrap(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn messages_in_systems() {
+        let mut world = World::new();
+
+        let mut sender = IntoSystem::into_system(|mut sender: ReactionSender<u32>| {
+            sender.send(1);
+            sender.send(2);
+        });
+        let mut receiver = IntoSystem::into_system(|mut receiver: ReactionReceiver<u32>| {
+            receiver.read().copied().collect::<Vec<_>>()
+        });
+        sender.initialize(&mut world);
+        receiver.initialize(&mut world);
+
+        sender.run((), &mut world);
+        sender.apply_deferred(&mut world);
+        assert_eq!(receiver.run((), &mut world), [1, 2]);
+        assert!(receiver.run((), &mut world).is_empty());
+    }
+}
@@ -1,8 +1,13 @@
+use crate::system_param::get_state;
 use bevy_ecs::{
+    component::Tick,
+    entity::EntityHashMap,
+    event::ManualEventReader,
     prelude::*,
-    query::{QueryData, QueryFilter},
+    query::{QueryData, QueryFilter, QueryState},
+    removal_detection::RemovedComponentEntity,
     system::SystemState,
-    world::DeferredWorld,
+    world::{unsafe_world_cell::UnsafeWorldCell, DeferredWorld},
 };
 use std::mem;
 
@@ -19,31 +24,96 @@ pub trait ReactiveQueryData<F: QueryFilter>: QueryData + Sized {
         entity: Entity,
     ) -> bool;
 
-    fn get<'w, 's>(
-        world: &'w mut DeferredWorld<'w>,
+    /// Returns the entities that changed since the last call to [`ReactiveQueryData::is_changed`],
+    /// without resetting its change detection.
+    ///
+    /// Defaults to every entity matching the query.
+    fn changed_entities(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveQueryData<F>>::State,
+    ) -> impl Iterator<Item = Entity> {
+        // SAFETY: The query only reads entities, and doesn't outlive `world`.
+        let entities: Vec<_> = unsafe { Self::get(world.as_unsafe_world_cell_readonly(), state) }
+            .transmute_lens_filtered::<Entity, F>()
+            .query()
+            .iter()
+            .collect();
+        entities.into_iter()
+    }
+
+    /// Get the query.
+    ///
+    /// # Safety
+    /// `world` must have access to the query's data,
+    /// and no other parameter may access that data in a conflicting way while the query is alive.
+    unsafe fn get<'w, 's>(
+        world: UnsafeWorldCell<'w>,
         state: &'s mut <Self as ReactiveQueryData<F>>::State,
     ) -> Query<'w, 's, Self, F>;
 }
 
+/// State of a reactive query for a component `T`.
+pub struct ComponentQueryState<T: Component, F: QueryFilter + 'static> {
+    changed: QueryState<(), (Changed<T>, F)>,
+    last_run: Tick,
+    matches: QueryState<Entity, (With<T>, F)>,
+    matched_len: usize,
+    query: SystemState<Query<'static, 'static, &'static T, F>>,
+}
+
+impl<T: Component, F: QueryFilter + 'static> ComponentQueryState<T, F> {
+    /// Returns `true` if the number of matching entities changed since the last check.
+    ///
+    /// This catches entities that stop passing `F` (or lose `T`) without changing `T`.
+    fn update_matched_len(&mut self, world: &World) -> bool {
+        self.matches.update_archetypes(world);
+
+        let len = if <(With<T>, F)>::IS_ARCHETYPAL {
+            self.matches
+                .matched_archetypes()
+                .filter_map(|id| world.archetypes().get(id))
+                .map(|archetype| archetype.len())
+                .sum()
+        } else {
+            self.matches.iter_manual(world).count()
+        };
+
+        let is_changed = len != self.matched_len;
+        self.matched_len = len;
+        is_changed
+    }
+}
+
 impl<F, T> ReactiveQueryData<F> for &T
 where
     F: QueryFilter + 'static,
     T: Component,
 {
-    type State = SystemState<(
-        Query<'static, 'static, (), (Changed<T>, F)>,
-        Query<'static, 'static, &'static T, F>,
-    )>;
+    type State = ComponentQueryState<T, F>;
 
     fn init(world: &mut World) -> <Self as ReactiveQueryData<F>>::State {
-        SystemState::new(world)
+        ComponentQueryState {
+            changed: QueryState::new(world),
+            // Treat everything as changed on the first poll, like a new system.
+            last_run: Tick::new(world.change_tick().get().wrapping_sub(Tick::MAX.get())),
+            matches: QueryState::new(world),
+            matched_len: 0,
+            query: SystemState::new(world),
+        }
     }
 
     fn is_changed<'w>(
         world: DeferredWorld,
         state: &mut <Self as ReactiveQueryData<F>>::State,
     ) -> bool {
-        !state.get(&world).0.is_empty()
+        // Poll the change filter directly with our own ticks,
+        // instead of fetching a full `SystemState` each frame.
+        let this_run = world.increment_change_tick();
+        state.changed.update_archetypes(&world);
+
+        let is_changed = !state.changed.is_empty(&world, state.last_run, this_run);
+        state.last_run = this_run;
+        state.update_matched_len(&world) || is_changed
     }
 
     fn is_changed_with_entity(
@@ -51,14 +121,369 @@ where
         state: &mut <Self as ReactiveQueryData<F>>::State,
         entity: Entity,
     ) -> bool {
-        state.get(&world).0.get(entity).is_ok()
+        if !state.query.get(&world).contains(entity) {
+            return false;
+        }
+
+        // Read the ticks directly so checking one entity doesn't hide changes to another.
+        world
+            .entity(entity)
+            .get_ref::<T>()
+            .is_some_and(|value| value.is_changed())
+    }
+
+    fn changed_entities(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveQueryData<F>>::State,
+    ) -> impl Iterator<Item = Entity> {
+        let this_run = world.read_change_tick();
+        state.matches.update_archetypes(&world);
+
+        let entities: Vec<_> = state
+            .matches
+            .iter_manual(&world)
+            .filter(|entity| {
+                world
+                    .entity(*entity)
+                    .get_change_ticks::<T>()
+                    .is_some_and(|ticks| ticks.is_changed(state.last_run, this_run))
+            })
+            .collect();
+        entities.into_iter()
+    }
+
+    unsafe fn get<'w, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveQueryData<F>>::State,
+    ) -> Query<'w, 's, Self, F> {
+        // TODO verify safety
+        unsafe { mem::transmute(get_state(&mut state.query, world)) }
+    }
+}
+
+/// State of a reactive query for an optional component `T`.
+pub type OptionQueryState<T, F> = (
+    ComponentQueryState<T, F>,
+    SystemState<Query<'static, 'static, Option<&'static T>, F>>,
+);
+
+impl<F, T> ReactiveQueryData<F> for Option<&T>
+where
+    F: QueryFilter + 'static,
+    T: Component,
+{
+    type State = OptionQueryState<T, F>;
+
+    fn init(world: &mut World) -> <Self as ReactiveQueryData<F>>::State {
+        (
+            <&T as ReactiveQueryData<F>>::init(world),
+            SystemState::new(world),
+        )
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveQueryData<F>>::State) -> bool {
+        <&T as ReactiveQueryData<F>>::is_changed(world, &mut state.0)
+    }
+
+    fn is_changed_with_entity(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveQueryData<F>>::State,
+        entity: Entity,
+    ) -> bool {
+        <&T as ReactiveQueryData<F>>::is_changed_with_entity(world, &mut state.0, entity)
+    }
+
+    fn changed_entities(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveQueryData<F>>::State,
+    ) -> impl Iterator<Item = Entity> {
+        <&T as ReactiveQueryData<F>>::changed_entities(world, &mut state.0)
+    }
+
+    unsafe fn get<'w, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveQueryData<F>>::State,
+    ) -> Query<'w, 's, Self, F> {
+        // TODO verify safety
+        unsafe { mem::transmute(get_state(&mut state.1, world)) }
+    }
+}
+
+/// State of a reactive query for a change-aware reference to a component `T`.
+pub type RefQueryState<T, F> = (
+    ComponentQueryState<T, F>,
+    SystemState<Query<'static, 'static, Ref<'static, T>, F>>,
+);
+
+/// Query for a component `T` with its change ticks, that changes like `&T`.
+///
+/// [`Ref::is_added`] and [`Ref::is_changed`] are relative to the last time the query was fetched,
+/// usually the last run of the reaction, like in a regular system.
+impl<F, T> ReactiveQueryData<F> for Ref<'_, T>
+where
+    F: QueryFilter + 'static,
+    T: Component,
+{
+    type State = RefQueryState<T, F>;
+
+    fn init(world: &mut World) -> <Self as ReactiveQueryData<F>>::State {
+        (
+            <&T as ReactiveQueryData<F>>::init(world),
+            SystemState::new(world),
+        )
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveQueryData<F>>::State) -> bool {
+        <&T as ReactiveQueryData<F>>::is_changed(world, &mut state.0)
+    }
+
+    fn is_changed_with_entity(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveQueryData<F>>::State,
+        entity: Entity,
+    ) -> bool {
+        <&T as ReactiveQueryData<F>>::is_changed_with_entity(world, &mut state.0, entity)
+    }
+
+    fn changed_entities(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveQueryData<F>>::State,
+    ) -> impl Iterator<Item = Entity> {
+        <&T as ReactiveQueryData<F>>::changed_entities(world, &mut state.0)
     }
 
-    fn get<'w, 's>(
-        world: &'w mut DeferredWorld<'w>,
+    unsafe fn get<'w, 's>(
+        world: UnsafeWorldCell<'w>,
         state: &'s mut <Self as ReactiveQueryData<F>>::State,
     ) -> Query<'w, 's, Self, F> {
         // TODO verify safety
-        unsafe { mem::transmute(state.get(world).1) }
+        unsafe { mem::transmute(get_state(&mut state.1, world)) }
+    }
+}
+
+/// State of a reactive query for the presence of a component `T`.
+pub struct HasQueryState<T: Component, F: QueryFilter + 'static> {
+    added: QueryState<(), (Added<T>, F)>,
+    last_run: Tick,
+    removed: ManualEventReader<RemovedComponentEntity>,
+    /// Whether each polled entity had `T` when it was last polled.
+    presence: EntityHashMap<bool>,
+    /// Change tick of the first poll pass, where every entity is changed like in a new system.
+    first_pass: Option<Tick>,
+    query: SystemState<Query<'static, 'static, Has<T>, F>>,
+}
+
+/// Query for the presence of `T`, that changes when `T` is added or removed.
+impl<F, T> ReactiveQueryData<F> for Has<T>
+where
+    F: QueryFilter + 'static,
+    T: Component,
+{
+    type State = HasQueryState<T, F>;
+
+    fn init(world: &mut World) -> <Self as ReactiveQueryData<F>>::State {
+        HasQueryState {
+            added: QueryState::new(world),
+            // Treat everything as changed on the first poll, like a new system.
+            last_run: Tick::new(world.change_tick().get().wrapping_sub(Tick::MAX.get())),
+            removed: ManualEventReader::default(),
+            presence: EntityHashMap::default(),
+            first_pass: None,
+            query: SystemState::new(world),
+        }
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveQueryData<F>>::State) -> bool {
+        let this_run = world.increment_change_tick();
+        state.added.update_archetypes(&world);
+
+        let mut is_changed = !state.added.is_empty(&world, state.last_run, this_run);
+        state.last_run = this_run;
+
+        if let Some(events) = world
+            .components()
+            .component_id::<T>()
+            .and_then(|id| world.removed_components().get(id))
+        {
+            is_changed |= !state.removed.is_empty(events);
+            state.removed.clear(events);
+        }
+
+        is_changed
+    }
+
+    fn is_changed_with_entity(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveQueryData<F>>::State,
+        entity: Entity,
+    ) -> bool {
+        let has = state.query.get(&world).get(entity).unwrap_or(false);
+
+        // Remember whether each entity had `T`, so removals are seen even without ticks.
+        let first_pass = *state.first_pass.get_or_insert(world.last_change_tick());
+        match state.presence.insert(entity, has) {
+            None if first_pass == world.last_change_tick() => true,
+            Some(had) if had != has => true,
+            _ => {
+                has && world
+                    .entity(entity)
+                    .get_ref::<T>()
+                    .is_some_and(|value| value.is_added())
+            }
+        }
+    }
+
+    unsafe fn get<'w, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveQueryData<F>>::State,
+    ) -> Query<'w, 's, Self, F> {
+        // SAFETY: The caller ensures `world` has access to the query's data.
+        unsafe { get_state(&mut state.query, world) }
+    }
+}
+
+/// State of a reactive query with no data, that only checks its filter `F`.
+pub struct FilterQueryState<F: QueryFilter + 'static> {
+    filter: QueryState<(), F>,
+    last_run: Tick,
+    matched_len: usize,
+    query: SystemState<Query<'static, 'static, (), F>>,
+}
+
+/// Query with no data, like `Query<(), (Changed<T>, With<U>)>`, used as a lightweight trigger.
+///
+/// Filters with change detection are changed when any entity passes them.
+/// Other filters are only changed when the number of matching entities changes,
+/// and aren't tracked per target.
+impl<F> ReactiveQueryData<F> for ()
+where
+    F: QueryFilter + 'static,
+{
+    type State = FilterQueryState<F>;
+
+    fn init(world: &mut World) -> <Self as ReactiveQueryData<F>>::State {
+        FilterQueryState {
+            filter: QueryState::new(world),
+            // Treat everything as changed on the first poll, like a new system.
+            last_run: Tick::new(world.change_tick().get().wrapping_sub(Tick::MAX.get())),
+            matched_len: 0,
+            query: SystemState::new(world),
+        }
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveQueryData<F>>::State) -> bool {
+        state.filter.update_archetypes(&world);
+
+        if F::IS_ARCHETYPAL {
+            let len = state.filter.iter_manual(&world).count();
+            let is_changed = len != state.matched_len;
+            state.matched_len = len;
+            return is_changed;
+        }
+
+        let this_run = world.increment_change_tick();
+        let is_changed = !state.filter.is_empty(&world, state.last_run, this_run);
+        state.last_run = this_run;
+        is_changed
+    }
+
+    fn is_changed_with_entity(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveQueryData<F>>::State,
+        entity: Entity,
+    ) -> bool {
+        if F::IS_ARCHETYPAL {
+            return false;
+        }
+
+        state.filter.update_archetypes(&world);
+        state.filter.get_manual(&world, entity).is_ok()
+    }
+
+    unsafe fn get<'w, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveQueryData<F>>::State,
+    ) -> Query<'w, 's, Self, F> {
+        // SAFETY: The caller ensures `world` has access to the query's data.
+        unsafe { get_state(&mut state.query, world) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::ReactionTestApp, Reaction, ReactiveQuery, Scope};
+
+    #[derive(Component)]
+    struct Health(i32);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct SawChange(bool);
+
+    #[test]
+    fn ref_queries_change_like_references() {
+        let mut app = ReactionTestApp::new();
+        let a = app.spawn_target(Health(1));
+        let b = app.spawn_target(Health(2));
+        let mut reaction = Reaction::new(
+            |scope: In<Scope>, mut query: ReactiveQuery<Ref<Health>>, mut commands: Commands| {
+                let health = query.get(scope.entity).unwrap();
+                commands
+                    .entity(scope.entity)
+                    .insert(SawChange(health.is_changed()));
+            },
+        );
+        reaction.add_target(a).add_target(b);
+        let reaction = app.spawn(reaction);
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+        assert_eq!(app.world().get::<SawChange>(a), Some(&SawChange(true)));
+
+        app.world_mut().entity_mut(b).remove::<SawChange>();
+        app.world_mut().get_mut::<Health>(a).unwrap().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), 3);
+        assert_eq!(app.world().get::<SawChange>(a), Some(&SawChange(true)));
+        assert_eq!(app.world().get::<SawChange>(b), None);
+    }
+
+    #[derive(Component)]
+    struct Shield(i32);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Shielded(bool);
+
+    #[test]
+    fn has_queries_change_when_added_or_removed() {
+        let mut app = ReactionTestApp::new();
+        let a = app.spawn_target(Health(1));
+        let b = app.spawn_target((Health(2), Shield(1)));
+        let mut reaction = Reaction::new(
+            |scope: In<Scope>, mut query: ReactiveQuery<Has<Shield>>, mut commands: Commands| {
+                let has_shield = query.get(scope.entity).unwrap();
+                commands.entity(scope.entity).insert(Shielded(has_shield));
+            },
+        );
+        reaction.add_target(a).add_target(b);
+        let reaction = app.spawn(reaction);
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+        assert_eq!(app.world().get::<Shielded>(a), Some(&Shielded(false)));
+        assert_eq!(app.world().get::<Shielded>(b), Some(&Shielded(true)));
+
+        // Changing the value of `Shield` isn't a change in its presence.
+        app.world_mut().get_mut::<Shield>(b).unwrap().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+
+        app.world_mut().entity_mut(a).insert(Shield(1));
+        app.step();
+        assert_eq!(app.runs(reaction), 3);
+        assert_eq!(app.world().get::<Shielded>(a), Some(&Shielded(true)));
+
+        app.world_mut().entity_mut(b).remove::<Shield>();
+        app.step();
+        assert_eq!(app.runs(reaction), 4);
+        assert_eq!(app.world().get::<Shielded>(b), Some(&Shielded(false)));
     }
 }
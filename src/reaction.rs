@@ -1,22 +1,367 @@
-use crate::{IntoReactiveSystem, ReactiveSystem, ReactiveSystemParamFunction, Scope};
-use bevy_app::PostUpdate;
+use crate::{
+    error::{self, ReactionError},
+    system_param::{add_deferred_access, write_all_access},
+    wake::{self, ReactionSubscriptions, Subscribed},
+    AsyncReaction, BoxedReactiveSystem, DiffBundle, FromWorldSystem, IntoLatestSources,
+    IntoReactiveSystem, LatestSources, OnChanged, OnRemoved, Peek, ReactionDiagnostics,
+    ReactionRegistry, ReactiveSystem, ReactiveSystemParamFunction, Scope, WithInput,
+};
+use bevy_app::{FixedUpdate, PostUpdate};
+#[cfg(feature = "frame_count")]
+use bevy_core::FrameCount;
 use bevy_ecs::{
-    component::{ComponentHooks, StorageType},
+    component::{ComponentHooks, ComponentId, StorageType, Tick},
+    entity::{EntityHashMap, EntityHashSet},
     prelude::*,
-    schedule::ScheduleLabel,
-    world::DeferredWorld,
+    query::FilteredAccessSet,
+    schedule::{InternedScheduleLabel, ScheduleLabel},
+    world::{Command, DeferredWorld},
+};
+use bevy_hierarchy::{BuildChildren, DespawnRecursiveExt};
+#[cfg(feature = "state")]
+use bevy_state::state::{State, States};
+#[cfg(feature = "time")]
+use bevy_time::Time;
+use bevy_utils::{tracing::error, FixedState, HashMap};
+#[cfg(feature = "time")]
+use std::time::Duration;
+use std::{
+    borrow::Cow,
+    error::Error,
+    future::Future,
+    hash::{BuildHasher, Hash},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
 };
-use bevy_hierarchy::BuildChildren;
-use std::sync::{Arc, Mutex};
+
+/// Key of a group of reactions that share change detection.
+///
+/// See [`Reaction::in_group`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GroupKey(u64);
+
+impl GroupKey {
+    /// Create a new key by hashing `key`.
+    pub fn new(key: impl Hash) -> Self {
+        Self(FixedState.hash_one(key))
+    }
+}
+
+type Predicate = Box<dyn Fn(&World) -> bool + Send + Sync>;
+
+type EntityPredicate = fn(&World, Entity) -> bool;
+
+type SubscribeFn = fn(&mut World, Entity);
+
+type ConfigFn = Box<dyn FnOnce(&mut EntityWorldMut) + Send>;
 
 pub(crate) struct Inner {
-    system: Box<dyn ReactiveSystem<In = (), Out = ()>>,
+    system: BoxedReactiveSystem,
+    name: Option<Cow<'static, str>>,
     entities: Vec<Entity>,
+    owned: Vec<Entity>,
+    #[cfg(feature = "time")]
+    ttl: Option<Duration>,
+    #[cfg(feature = "time")]
+    spawned_at: Option<Duration>,
+    chunk_size: Option<usize>,
+    cursor: usize,
+    pending: EntityHashSet,
+    on_exit: bool,
+    exited: bool,
+    init_if: Option<Predicate>,
+    run_if: Option<Predicate>,
+    should_run: Option<Predicate>,
+    requires: Vec<EntityPredicate>,
+    entered_if: Option<Predicate>,
+    reset_on_enter: bool,
+    skip_initial: bool,
+    last_run: Option<Tick>,
+    #[cfg(feature = "frame_count")]
+    last_run_frame: Option<u32>,
+    run_count: usize,
+    also_in: Vec<InternedScheduleLabel>,
+    last_polled: Option<Tick>,
+    is_init: bool,
+    is_enabled: bool,
+    access: FilteredAccessSet<ComponentId>,
+    subscriptions: Vec<SubscribeFn>,
+    configs: Vec<ConfigFn>,
+    is_poisoned: bool,
+}
+
+impl Inner {
+    fn new(system: BoxedReactiveSystem) -> Self {
+        // Until initialized, a reaction may access anything.
+        let access = write_all_access();
+
+        Self {
+            system,
+            name: None,
+            entities: Vec::new(),
+            owned: Vec::new(),
+            #[cfg(feature = "time")]
+            ttl: None,
+            #[cfg(feature = "time")]
+            spawned_at: None,
+            chunk_size: None,
+            cursor: 0,
+            pending: EntityHashSet::default(),
+            on_exit: false,
+            exited: false,
+            init_if: None,
+            run_if: None,
+            should_run: None,
+            requires: Vec::new(),
+            entered_if: None,
+            reset_on_enter: false,
+            skip_initial: false,
+            last_run: None,
+            #[cfg(feature = "frame_count")]
+            last_run_frame: None,
+            run_count: 0,
+            also_in: Vec::new(),
+            last_polled: None,
+            is_init: false,
+            is_enabled: true,
+            access,
+            subscriptions: Vec::new(),
+            configs: Vec::new(),
+            is_poisoned: false,
+        }
+    }
+
+    fn init(&mut self, world: &mut World) {
+        if self.init_if.as_ref().is_some_and(|f| !f(world)) {
+            return;
+        }
+
+        self.system.init(world);
+        #[cfg(feature = "time")]
+        if self.spawned_at.is_none() {
+            self.spawned_at = world.get_resource::<Time>().map(|time| time.elapsed());
+        }
+        self.is_init = true;
+
+        self.access = self.system.access();
+        if self.has_ttl() || self.reset_on_enter {
+            add_deferred_access(world, &mut self.access);
+        }
+    }
+
+    /// Returns `true` if this reaction despawns itself after a TTL.
+    #[cfg(feature = "time")]
+    fn has_ttl(&self) -> bool {
+        self.ttl.is_some()
+    }
+
+    #[cfg(not(feature = "time"))]
+    fn has_ttl(&self) -> bool {
+        false
+    }
+
+    /// Initialize the system again the next time this reaction runs.
+    ///
+    /// The access of the new system isn't known until then, so it may access anything.
+    fn invalidate(&mut self) {
+        self.is_init = false;
+        self.access = write_all_access();
+    }
+
+    /// Record that the system ran at the current change tick and frame of `world`.
+    fn mark_run(&mut self, world: &World) {
+        self.last_run = Some(world.read_change_tick());
+        #[cfg(feature = "frame_count")]
+        if let Some(frame_count) = world.get_resource::<FrameCount>() {
+            self.last_run_frame = Some(frame_count.0);
+        }
+    }
+
+    /// Run the system for each target (or `entity` if there are none), regardless of changes.
+    fn run_all(&mut self, mut world: DeferredWorld, entity: Entity) {
+        if self.entities.is_empty() {
+            if !is_skipped(&world, &self.requires, entity) {
+                run_system(
+                    &mut self.system,
+                    &mut self.run_count,
+                    world.reborrow(),
+                    entity,
+                );
+            }
+        } else {
+            for entity in &self.entities {
+                if !is_skipped(&world, &self.requires, *entity) {
+                    run_system(
+                        &mut self.system,
+                        &mut self.run_count,
+                        world.reborrow(),
+                        *entity,
+                    );
+                }
+            }
+        }
+        self.mark_run(&world);
+    }
+
+    /// Poll the system for each target (or `entity` if there are none) without running it,
+    /// so only later changes are seen.
+    fn poll_all(&mut self, mut world: DeferredWorld) {
+        if self.entities.is_empty() {
+            is_changed(&mut self.system, world, None);
+        } else {
+            for entity in &self.entities {
+                if !is_skipped(&world, &self.requires, *entity) {
+                    is_changed(&mut self.system, world.reborrow(), Some(*entity));
+                }
+            }
+        }
+    }
+
+    /// Poll and run the system for each target (or `entity` if there are none),
+    /// running it even if nothing changed.
+    fn rerun_all(&mut self, mut world: DeferredWorld, entity: Entity) -> usize {
+        let mut ran = 0;
+        if self.entities.is_empty() {
+            if !is_skipped(&world, &self.requires, entity) {
+                is_changed(&mut self.system, world.reborrow(), None);
+                run_system(
+                    &mut self.system,
+                    &mut self.run_count,
+                    world.reborrow(),
+                    entity,
+                );
+                ran += 1;
+            }
+        } else {
+            for entity in &self.entities {
+                if !is_skipped(&world, &self.requires, *entity) {
+                    is_changed(&mut self.system, world.reborrow(), Some(*entity));
+                    run_system(
+                        &mut self.system,
+                        &mut self.run_count,
+                        world.reborrow(),
+                        *entity,
+                    );
+                    ran += 1;
+                }
+            }
+        }
+        self.mark_run(&world);
+        ran
+    }
+}
+
+/// Returns `true` if `entity` should be skipped,
+/// because it was despawned or is missing a component from [`Reaction::requires`].
+fn is_skipped(world: &World, requires: &[EntityPredicate], entity: Entity) -> bool {
+    world.get_entity(entity).is_none() || requires.iter().any(|has| !has(world, entity))
+}
+
+/// Returns `true` if `entity` has the component `C`.
+fn has_component<C: Component>(world: &World, entity: Entity) -> bool {
+    world.get::<C>(entity).is_some()
+}
+
+/// Lock `inner`, even if a reaction panicked while holding it.
+fn lock(inner: &Mutex<Inner>) -> MutexGuard<'_, Inner> {
+    inner.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Check if `system` has changed for `target`, or at all if there's no target.
+fn is_changed(
+    system: &mut BoxedReactiveSystem,
+    world: DeferredWorld,
+    target: Option<Entity>,
+) -> bool {
+    #[cfg(feature = "trace")]
+    let _span = tracing::info_span!("is_changed", target = ?target).entered();
+
+    match target {
+        Some(target) => system.is_changed_with_entity(world, target),
+        None => system.is_changed(world),
+    }
+}
+
+/// Run `system` for `entity`, counting the run in `run_count`.
+fn run_system(
+    system: &mut BoxedReactiveSystem,
+    run_count: &mut usize,
+    world: DeferredWorld,
+    entity: Entity,
+) {
+    #[cfg(feature = "trace")]
+    let _span = tracing::info_span!("run", entity = ?entity).entered();
+
+    system.run((), world, entity);
+    *run_count += 1;
+}
+
+/// Handle to modify a [`Reaction`] without querying for its component.
+///
+/// The handle shares its state with the reaction, including every clone of it,
+/// so changes are seen the next time the reaction runs.
+/// If the reaction is despawned, the handle can still be used but has no effect.
+#[derive(Clone)]
+pub struct ReactionHandle {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl ReactionHandle {
+    /// Add `entity` as a target of the reaction.
+    pub fn add_target(&self, entity: Entity) {
+        lock(&self.inner).entities.push(entity);
+    }
+
+    /// Remove `entity` from the targets of the reaction.
+    ///
+    /// Once the last target is removed, the reaction runs for its own entity again.
+    pub fn remove_target(&self, entity: Entity) {
+        let inner = &mut *lock(&self.inner);
+        inner.entities.retain(|target| *target != entity);
+        inner.pending.remove(&entity);
+    }
+
+    /// Returns the targets of the reaction.
+    pub fn targets(&self) -> Vec<Entity> {
+        lock(&self.inner).entities.clone()
+    }
+
+    /// Enable or disable the reaction. Disabled reactions aren't polled or run.
+    pub fn set_enabled(&self, is_enabled: bool) {
+        lock(&self.inner).is_enabled = is_enabled;
+    }
+
+    /// Returns `true` if the reaction is enabled.
+    pub fn is_enabled(&self) -> bool {
+        lock(&self.inner).is_enabled
+    }
+}
+
+/// A [`Reaction`] that also runs in other schedules, see [`Reaction::also_in`].
+///
+/// These are run by [`react_shared`](crate::react_shared) instead of the other drivers.
+#[derive(Component)]
+pub struct SharedReaction {
+    reaction: Reaction<InternedScheduleLabel>,
+    labels: Vec<InternedScheduleLabel>,
+}
+
+impl SharedReaction {
+    /// Returns the reaction, with its own label.
+    pub fn reaction(&self) -> &Reaction<InternedScheduleLabel> {
+        &self.reaction
+    }
+
+    /// Returns `true` if the reaction runs for `label`.
+    pub fn runs_in(&self, label: InternedScheduleLabel) -> bool {
+        self.labels.contains(&label)
+    }
 }
 
 #[derive(Clone)]
 pub struct Reaction<L = PostUpdate> {
     inner: Arc<Mutex<Inner>>,
+    priority: i32,
+    group: Option<GroupKey>,
     _label: L,
 }
 
@@ -25,72 +370,832 @@ impl<L: ScheduleLabel> Component for Reaction<L> {
 
     fn register_component_hooks(hooks: &mut ComponentHooks) {
         hooks.on_insert(|mut world, entity, _| {
+            let reaction = world.get::<Reaction<L>>(entity).unwrap();
+            let reaction = reaction.with_label(reaction._label.intern());
+            if let Some(mut registry) = world.get_resource_mut::<ReactionRegistry>() {
+                registry.insert(entity, reaction);
+            }
+
             world.commands().add(move |world: &mut World| {
-                let inner = world
-                    .query::<&Reaction<L>>()
-                    .get(world, entity)
-                    .unwrap()
-                    .inner
-                    .clone();
-                inner.lock().unwrap().system.init(world);
+                let reaction = world.query::<&Reaction<L>>().get(world, entity).unwrap();
+                let reaction = reaction.with_label(reaction._label.intern());
+                let inner = &mut *lock(&reaction.inner);
+                for config in inner.configs.drain(..) {
+                    config(&mut world.entity_mut(entity));
+                }
+                if inner.is_init {
+                    // This reaction was inserted again, so reset it like `Reaction::reset`.
+                    inner.invalidate();
+                } else {
+                    inner.init(world);
+                }
+
+                if !inner.subscriptions.is_empty() {
+                    for subscribe in &inner.subscriptions {
+                        subscribe(world, entity);
+                    }
+                    world.entity_mut(entity).insert(Subscribed);
+                }
+
+                if !inner.also_in.is_empty() {
+                    let mut labels = vec![reaction._label];
+                    labels.extend(inner.also_in.iter().copied());
+                    world.entity_mut(entity).insert(SharedReaction {
+                        reaction: reaction.clone(),
+                        labels,
+                    });
+                }
             });
         });
+        hooks.on_remove(|mut world, entity, _| {
+            if let Some(mut registry) = world.get_resource_mut::<ReactionRegistry>() {
+                registry.remove(entity);
+            }
+
+            let inner = world.get::<Reaction<L>>(entity).unwrap().inner.clone();
+            let owned = std::mem::take(&mut lock(&inner).owned);
+            let mut commands = world.commands();
+            for target in owned {
+                if let Some(target) = commands.get_entity(target) {
+                    target.despawn_recursive();
+                }
+            }
+
+            if let Some(mut subscriptions) = world.get_resource_mut::<ReactionSubscriptions>() {
+                subscriptions.unsubscribe(entity);
+            }
+
+            if !lock(&inner).also_in.is_empty() {
+                world.commands().entity(entity).remove::<SharedReaction>();
+            }
+        });
     }
 }
 
-impl<L: ScheduleLabel> Reaction<L> {
-    pub fn from_label<Marker, S>(
-        label: L,
-        system: impl IntoReactiveSystem<Marker, System = S>,
+impl<L: ScheduleLabel> Reaction<L> {
+    pub fn from_label<Marker, S>(
+        label: L,
+        system: impl IntoReactiveSystem<Marker, System = S>,
+    ) -> Self
+    where
+        Marker: Send + Sync + 'static,
+        S: ReactiveSystem<In = (), Out = ()> + 'static,
+    {
+        Self {
+            inner: Arc::new(Mutex::new(Inner::new(Box::new(
+                system.into_reactive_system(),
+            )))),
+            priority: 0,
+            group: None,
+            _label: label,
+        }
+    }
+
+    pub fn with_label<L2>(&self, label: L2) -> Reaction<L2> {
+        let inner = self.inner.clone();
+        Reaction {
+            inner,
+            priority: self.priority,
+            group: self.group,
+            _label: label,
+        }
+    }
+
+    pub fn add_target(&mut self, entity: Entity) -> &mut Self {
+        lock(&self.inner).entities.push(entity);
+        self
+    }
+
+    /// Returns a [`ReactionHandle`] to modify this reaction after it's spawned.
+    pub fn handle(&self) -> ReactionHandle {
+        ReactionHandle {
+            inner: self.inner.clone(),
+        }
+    }
+
+    /// Spawn a new entity and add it as a target of this reaction.
+    ///
+    /// The target is owned by this reaction and despawned when the reaction is removed.
+    pub fn spawn_target(&mut self, commands: &mut Commands) -> Entity {
+        let entity = commands.spawn_empty().id();
+        let inner = &mut *lock(&self.inner);
+        inner.entities.push(entity);
+        inner.owned.push(entity);
+        entity
+    }
+
+    /// Also run this reaction in the schedule for `label`, sharing its state with its own label.
+    ///
+    /// The label needs to be added to the [`ReactionPlugin`](crate::ReactionPlugin),
+    /// like [`ReactionPlugin::with_label`](crate::ReactionPlugin::with_label).
+    /// Each time it's polled, the reaction only sees changes made since it was last polled
+    /// in any of its schedules, so a change only runs it once.
+    /// These reactions are run one at a time by [`react_shared`](crate::react_shared).
+    pub fn also_in(self, label: impl ScheduleLabel) -> Self {
+        lock(&self.inner).also_in.push(label.intern());
+        self
+    }
+
+    /// Returns the change tick this reaction was last polled at by [`react_shared`](crate::react_shared).
+    pub(crate) fn last_polled(&self) -> Option<Tick> {
+        lock(&self.inner).last_polled
+    }
+
+    pub(crate) fn set_last_polled(&self, tick: Tick) {
+        lock(&self.inner).last_polled = Some(tick);
+    }
+
+    /// Replace the system of this reaction, keeping its targets and label.
+    ///
+    /// The new system is initialized and run for every target the next time this reaction runs.
+    pub fn set_system<Marker, S>(&mut self, system: impl IntoReactiveSystem<Marker, System = S>)
+    where
+        Marker: Send + Sync + 'static,
+        S: ReactiveSystem<In = (), Out = ()> + 'static,
+    {
+        let inner = &mut *lock(&self.inner);
+        inner.system = Box::new(system.into_reactive_system());
+        inner.invalidate();
+    }
+
+    /// Reset the system of this reaction, including its [`Local`]s.
+    ///
+    /// The system is initialized again and run for every target the next time this reaction runs,
+    /// like after [`Reaction::set_system`].
+    /// Inserting a reaction that was already spawned, like after removing it, also resets it.
+    pub fn reset(&self) {
+        lock(&self.inner).invalidate();
+    }
+
+    /// Despawn this reaction's entity (and its descendants) once `ttl` has elapsed since it was spawned.
+    ///
+    /// Elapsed time is read from the [`Time`] resource.
+    #[cfg(feature = "time")]
+    pub fn with_ttl(self, ttl: Duration) -> Self {
+        lock(&self.inner).ttl = Some(ttl);
+        self
+    }
+
+    /// Set the name of this reaction, used for diagnostics.
+    ///
+    /// With the `trace` feature, this names the spans of this reaction.
+    pub fn named(self, name: impl Into<Cow<'static, str>>) -> Self {
+        lock(&self.inner).name = Some(name.into());
+        self
+    }
+
+    /// Set the priority of this reaction.
+    ///
+    /// Reactions with a higher priority run after others in the same schedule,
+    /// so their commands are applied last. Defaults to `0`.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Returns the priority of this reaction.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Add this reaction to the group `key`.
+    ///
+    /// Reactions in the same group share one change detection check each time they're run
+    /// by [`react`](crate::react) or [`react_observed`](crate::react_observed):
+    /// the first reaction in the group is polled, and the rest run only if it changed.
+    /// Only reactions without targets share their check.
+    pub fn in_group(mut self, key: impl Hash) -> Self {
+        self.group = Some(GroupKey::new(key));
+        self
+    }
+
+    /// Returns the group of this reaction, if it was added to one with [`Reaction::in_group`].
+    pub fn group(&self) -> Option<GroupKey> {
+        self.group
+    }
+
+    /// Returns the label of this reaction.
+    pub fn label(&self) -> &L {
+        &self._label
+    }
+
+    /// Returns the name of this reaction, if it was named with [`Reaction::named`].
+    pub fn name(&self) -> Option<Cow<'static, str>> {
+        lock(&self.inner).name.clone()
+    }
+
+    /// Returns the number of targets of this reaction.
+    pub fn target_count(&self) -> usize {
+        lock(&self.inner).entities.len()
+    }
+
+    /// Returns the change tick of the world when this reaction last ran,
+    /// or `None` if it never ran.
+    ///
+    /// Compare this with [`World::change_tick`] to find stale reactions.
+    pub fn last_run(&self) -> Option<Tick> {
+        lock(&self.inner).last_run
+    }
+
+    /// Returns the [`FrameCount`] of the last frame this reaction ran in.
+    ///
+    /// This is `None` until the reaction runs with a [`FrameCount`] resource.
+    #[cfg(feature = "frame_count")]
+    pub fn last_run_frame(&self) -> Option<u32> {
+        lock(&self.inner).last_run_frame
+    }
+
+    /// Returns the number of times the system of this reaction ran, counting each target.
+    pub fn run_count(&self) -> usize {
+        lock(&self.inner).run_count
+    }
+
+    /// Insert `config` on this reaction's entity when it's spawned.
+    ///
+    /// Without targets, [`Scope::entity`] is the reaction's entity,
+    /// so the config can be read with [`ReactiveQuery::get_scope`](crate::ReactiveQuery::get_scope).
+    pub fn with_config<C: Component>(self, config: C) -> Self {
+        lock(&self.inner).configs.push(Box::new(move |entity| {
+            entity.insert(config);
+        }));
+        self
+    }
+
+    /// Wake this reaction whenever a `T` is inserted.
+    ///
+    /// Reactions with subscriptions are only run by [`react_observed`](crate::react_observed)
+    /// after they're woken up, so they have no cost while idle.
+    /// Woken reactions still only run if their parameters have changed,
+    /// and mutating an existing `T` in place won't wake the reaction.
+    pub fn wake_on<T: Component>(self) -> Self {
+        lock(&self.inner).subscriptions.push(wake::subscribe::<T>);
+        self
+    }
+
+    /// Run at most `chunk_size` targets each time this reaction runs.
+    ///
+    /// Changed targets are queued and run in turn over the following passes.
+    pub fn chunked_targets(self, chunk_size: usize) -> Self {
+        lock(&self.inner).chunk_size = Some(chunk_size);
+        self
+    }
+
+    /// Only initialize this reaction once `predicate` returns `true`.
+    ///
+    /// The predicate is checked again each time this reaction runs until it succeeds,
+    /// and the reaction stays dormant until then. Once initialized, it runs immediately.
+    pub fn init_if(self, predicate: impl Fn(&World) -> bool + Send + Sync + 'static) -> Self {
+        lock(&self.inner).init_if = Some(Box::new(predicate));
+        self
+    }
+
+    /// Only poll and run this reaction while `predicate` returns `true`.
+    ///
+    /// The predicate is checked before polling for changes,
+    /// so a gated reaction does no work at all while it returns `false`.
+    pub fn run_if(self, predicate: impl Fn(&World) -> bool + Send + Sync + 'static) -> Self {
+        lock(&self.inner).run_if = Some(Box::new(predicate));
+        self
+    }
+
+    /// Only run this reaction for targets that currently have the component `C`,
+    /// or for the reaction entity if it has no targets.
+    ///
+    /// Unlike change detection, this is checked every time the reaction is polled.
+    /// Targets without `C` aren't polled or run, so the system keeps its state
+    /// until `C` is back.
+    pub fn requires<C: Component>(self) -> Self {
+        lock(&self.inner).requires.push(has_component::<C>);
+        self
+    }
+
+    /// Also run this reaction for every target whenever `predicate` returns `true`,
+    /// in addition to when its system changes.
+    ///
+    /// This is useful for custom change detection, like comparing two unrelated values.
+    pub fn should_run(self, predicate: impl Fn(&World) -> bool + Send + Sync + 'static) -> Self {
+        lock(&self.inner).should_run = Some(Box::new(predicate));
+        self
+    }
+
+    /// Only run this reaction while the [`State`] of `S` is `state`,
+    /// and run it again each time that state is entered.
+    ///
+    /// This replaces any predicate set with [`Reaction::run_if`].
+    #[cfg(feature = "state")]
+    pub fn in_state<S: States>(self, state: S) -> Self {
+        let entered = state.clone();
+        {
+            let inner = &mut *lock(&self.inner);
+            inner.entered_if = Some(Box::new(move |world| {
+                world
+                    .get_resource_ref::<State<S>>()
+                    .is_some_and(|current| current.is_changed() && **current == entered)
+            }));
+        }
+        self.run_if(move |world| {
+            world
+                .get_resource::<State<S>>()
+                .is_some_and(|current| **current == state)
+        })
+    }
+
+    /// Reset the system of this reaction, including its [`Local`]s,
+    /// each time it enters the state from [`Reaction::in_state`].
+    #[cfg(feature = "state")]
+    pub fn reset_on_enter(self) -> Self {
+        lock(&self.inner).reset_on_enter = true;
+        self
+    }
+
+    /// Skip the first run of this reaction, so it only runs for changes after it's spawned.
+    ///
+    /// This is useful when the initial values are already correct.
+    /// Note that a [`ReactiveQuery`](crate::ReactiveQuery) only learns which entities to track
+    /// by running, so reactions that use one will still run the next time they're polled.
+    pub fn skip_initial(self) -> Self {
+        lock(&self.inner).skip_initial = true;
+        self
+    }
+
+    pub fn run(&self, world: DeferredWorld, entity: Entity) {
+        self.run_with_diagnostics(world, entity);
+    }
+
+    /// Run this reaction for its `entity` directly on `world`, like [`Reaction::run`].
+    ///
+    /// Pending commands are applied before running the reaction,
+    /// and commands queued by the reaction are applied before returning.
+    /// To run a spawned reaction, clone it out of the world first.
+    pub fn run_on(&self, world: &mut World, entity: Entity) {
+        // Apply pending commands first, so a newly spawned reaction is initialized.
+        world.flush();
+        self.run(world.into(), entity);
+        world.flush();
+    }
+
+    /// Run this reaction, returning the counts of its activity.
+    pub(crate) fn run_with_diagnostics(
+        &self,
+        world: DeferredWorld,
+        entity: Entity,
+    ) -> ReactionDiagnostics {
+        self.run_in_group(world, entity, None)
+    }
+
+    /// Run this reaction, using `group_changed` instead of polling for changes if it's `Some`.
+    pub(crate) fn run_in_group(
+        &self,
+        mut world: DeferredWorld,
+        entity: Entity,
+        group_changed: Option<bool>,
+    ) -> ReactionDiagnostics {
+        let mut diagnostics = ReactionDiagnostics::default();
+        let inner = &mut *match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(error) => {
+                // This reaction panicked during a previous run, so skip it from now on.
+                let mut inner = error.into_inner();
+                if !inner.is_poisoned {
+                    inner.is_poisoned = true;
+                    error!(
+                        "Skipping reaction {} ({entity}) after it panicked",
+                        inner.name.as_deref().unwrap_or("<unnamed>")
+                    );
+                }
+                return diagnostics;
+            }
+        };
+
+        #[cfg(feature = "trace")]
+        let _span = tracing::info_span!("reaction", name = inner.name.as_deref(), entity = ?entity)
+            .entered();
+
+        if !inner.is_init {
+            if inner.init_if.as_ref().is_none_or(|f| f(&world)) {
+                let inner = self.inner.clone();
+                world.commands().add(move |world: &mut World| {
+                    let inner = &mut *lock(&inner);
+                    if !inner.is_init {
+                        inner.init(world);
+                        let is_enabled =
+                            inner.is_enabled && inner.run_if.as_ref().is_none_or(|f| f(world));
+                        if inner.is_init && !inner.on_exit && is_enabled {
+                            if inner.skip_initial {
+                                inner.skip_initial = false;
+                                inner.poll_all(world.into());
+                            } else {
+                                inner.rerun_all(world.into(), entity);
+                            }
+                        }
+                    }
+                });
+            }
+            return diagnostics;
+        }
+
+        if inner.on_exit {
+            return diagnostics;
+        }
+
+        #[cfg(feature = "time")]
+        if let (Some(ttl), Some(time)) = (inner.ttl, world.get_resource::<Time>()) {
+            let now = time.elapsed();
+            let spawned_at = *inner.spawned_at.get_or_insert(now);
+            if now - spawned_at >= ttl {
+                inner.ttl = None;
+                world.commands().entity(entity).despawn_recursive();
+                return diagnostics;
+            }
+        }
+
+        if !inner.is_enabled || inner.run_if.as_ref().is_some_and(|f| !f(&world)) {
+            return diagnostics;
+        }
+
+        // Without targets, the reaction entity itself needs the required components.
+        if inner.entities.is_empty() && is_skipped(&world, &inner.requires, entity) {
+            return diagnostics;
+        }
+
+        diagnostics.polled = 1;
+        if inner.skip_initial {
+            inner.skip_initial = false;
+            inner.poll_all(world);
+            return diagnostics;
+        }
+
+        let group_changed = group_changed.filter(|_| inner.entities.is_empty());
+        if group_changed.is_some() {
+            diagnostics.polled = 0;
+        }
+
+        if inner.entered_if.as_ref().is_some_and(|f| f(&world)) {
+            diagnostics.changed = 1;
+
+            if inner.reset_on_enter {
+                diagnostics.ran = inner.entities.len().max(1);
+                let inner = self.inner.clone();
+                world.commands().add(move |world: &mut World| {
+                    let inner = &mut *lock(&inner);
+                    inner.system.init(world);
+                    inner.rerun_all(world.into(), entity);
+                });
+            } else {
+                diagnostics.ran = inner.rerun_all(world.reborrow(), entity);
+            }
+        } else if inner.should_run.as_ref().is_some_and(|f| f(&world)) {
+            diagnostics.changed = 1;
+            diagnostics.ran = inner.rerun_all(world.reborrow(), entity);
+        } else if inner.entities.is_empty() {
+            let is_changed = group_changed
+                .unwrap_or_else(|| is_changed(&mut inner.system, world.reborrow(), None));
+            if is_changed {
+                diagnostics.changed = 1;
+                diagnostics.ran = 1;
+                run_system(
+                    &mut inner.system,
+                    &mut inner.run_count,
+                    world.reborrow(),
+                    entity,
+                );
+            }
+        } else if let Some(chunk_size) = inner.chunk_size {
+            for entity in &inner.entities {
+                if is_skipped(&world, &inner.requires, *entity) {
+                    inner.pending.remove(entity);
+                } else if is_changed(&mut inner.system, world.reborrow(), Some(*entity)) {
+                    diagnostics.changed = 1;
+                    inner.pending.insert(*entity);
+                }
+            }
+
+            let mut count = 0;
+            for _ in 0..inner.entities.len() {
+                if count == chunk_size || inner.pending.is_empty() {
+                    break;
+                }
+
+                let entity = inner.entities[inner.cursor % inner.entities.len()];
+                inner.cursor = (inner.cursor + 1) % inner.entities.len();
+
+                if inner.pending.remove(&entity) {
+                    run_system(
+                        &mut inner.system,
+                        &mut inner.run_count,
+                        world.reborrow(),
+                        entity,
+                    );
+                    count += 1;
+                }
+            }
+            diagnostics.ran = count;
+        } else {
+            for entity in &inner.entities {
+                // Skip targets that were despawned earlier this frame, so queries for them don't fail,
+                // and targets missing a required component.
+                if is_skipped(&world, &inner.requires, *entity) {
+                    continue;
+                }
+
+                if is_changed(&mut inner.system, world.reborrow(), Some(*entity)) {
+                    diagnostics.changed = 1;
+                    diagnostics.ran += 1;
+                    run_system(
+                        &mut inner.system,
+                        &mut inner.run_count,
+                        world.reborrow(),
+                        *entity,
+                    );
+                }
+            }
+        }
+
+        if diagnostics.ran > 0 {
+            inner.mark_run(&world);
+        }
+
+        diagnostics
+    }
+
+    /// Returns the world access of this reaction.
+    pub(crate) fn access(&self) -> FilteredAccessSet<ComponentId> {
+        let inner = lock(&self.inner);
+        if !inner.is_init {
+            // The access of the system isn't known until it's initialized,
+            // and initializing it queues commands, so it needs to run alone.
+            return write_all_access();
+        }
+        inner.access.clone()
+    }
+
+    /// Run this reaction once if it was created with [`Reaction::on_exit`].
+    pub(crate) fn run_exit(&self, world: DeferredWorld, entity: Entity) {
+        let inner = &mut *lock(&self.inner);
+
+        if !inner.on_exit || inner.exited || !inner.is_init {
+            return;
+        }
+        inner.exited = true;
+        inner.run_all(world, entity);
+    }
+}
+
+impl Reaction {
+    pub fn new<Marker, S>(system: impl IntoReactiveSystem<Marker, System = S>) -> Self
+    where
+        Marker: Send + Sync + 'static,
+        S: ReactiveSystem<In = (), Out = ()> + 'static,
+    {
+        Self::from_label(PostUpdate, system)
+    }
+
+    /// Create a new [`Reaction`] that runs in [`FixedUpdate`].
+    ///
+    /// See [`ReactionPlugin::fixed_update`](crate::ReactionPlugin::fixed_update).
+    pub fn fixed<Marker, S>(
+        system: impl IntoReactiveSystem<Marker, System = S>,
+    ) -> Reaction<FixedUpdate>
+    where
+        Marker: Send + Sync + 'static,
+        S: ReactiveSystem<In = (), Out = ()> + 'static,
+    {
+        Reaction::from_label(FixedUpdate, system)
+    }
+
+    /// Create a new [`Reaction`] that runs the first time `system` changes,
+    /// and then never again.
+    pub fn run_once<Marker, S>(system: impl IntoReactiveSystem<Marker, System = S>) -> Self
+    where
+        Marker: Send + Sync + 'static,
+        S: ReactiveSystem<In = (), Out = ()> + 'static,
+    {
+        Self::new(system.run_once())
+    }
+
+    /// Create a new [`Reaction`] that runs once every `interval`,
+    /// regardless of changes to the parameters of `system`.
+    ///
+    /// See [`IntoReactiveSystem::every`].
+    #[cfg(feature = "time")]
+    pub fn every<Marker, S>(
+        interval: Duration,
+        system: impl IntoReactiveSystem<Marker, System = S>,
+    ) -> Self
+    where
+        Marker: Send + Sync + 'static,
+        S: ReactiveSystem<In = (), Out = ()> + 'static,
+    {
+        Self::new(system.every(interval))
+    }
+
+    /// Create a new [`Reaction`] with a system built from the [`World`] when it's initialized.
+    ///
+    /// This is useful for capturing state that isn't available at spawn time,
+    /// like [`Local`] does with [`FromWorld`].
+    pub fn from_world<Marker, F, I, S>(f: F) -> Self
+    where
+        Marker: Send + Sync + 'static,
+        F: FnOnce(&mut World) -> I + Send + Sync + 'static,
+        I: IntoReactiveSystem<Marker, System = S>,
+        S: ReactiveSystem<In = (), Out = ()> + 'static,
+    {
+        Self::new(FromWorldSystem::new(move |world: &mut World| {
+            f(world).into_reactive_system()
+        }))
+    }
+
+    /// Create a new [`Reaction`] from a system that can fail.
+    ///
+    /// Errors are passed to the [`ReactionErrorHandler`] instead of panicking,
+    /// which logs them by default.
+    pub fn try_new<Marker, S, E>(system: impl IntoReactiveSystem<Marker, System = S>) -> Self
+    where
+        Marker: Send + Sync + 'static,
+        S: ReactiveSystem<In = (), Out = Result<(), E>> + 'static,
+        E: Into<Box<dyn Error + Send + Sync>> + Send + Sync + 'static,
+    {
+        Self::new(
+            system.map(|scope: In<Scope<Result<(), E>>>, mut commands: Commands| {
+                let Scope { entity, input } = scope.0;
+                if let Err(error) = input {
+                    let error = ReactionError {
+                        entity,
+                        error: error.into(),
+                    };
+                    commands.add(move |world: &mut World| error::handle_error(world, error));
+                }
+            }),
+        )
+    }
+
+    /// Create a new [`Reaction`] that runs once when an [`AppExit`](bevy_app::AppExit) event is sent.
+    ///
+    /// Exit reactions never run from change detection.
+    /// Their commands are applied before the app exits.
+    pub fn on_exit<Marker, S>(system: impl IntoReactiveSystem<Marker, System = S>) -> Self
+    where
+        Marker: Send + Sync + 'static,
+        S: ReactiveSystem<In = (), Out = ()> + 'static,
+    {
+        let reaction = Self::new(system);
+        lock(&reaction.inner).on_exit = true;
+        reaction
+    }
+
+    /// Create a new [`Reaction`] that runs once for each entity whose component `T` changed,
+    /// with that entity as the [`Scope`] target and a clone of its new `T` as input.
+    ///
+    /// The value is cloned right before `system` runs, so it's the latest value
+    /// rather than the value when the change was detected.
+    /// Reactions with targets only run for the targets whose `T` changed,
+    /// otherwise no targets have to be added.
+    /// Changes to the parameters of `system` don't run the reaction.
+    pub fn on_changed<T, Marker, S>(system: impl IntoReactiveSystem<Marker, System = S>) -> Self
+    where
+        T: Component + Clone,
+        Marker: Send + Sync + 'static,
+        S: ReactiveSystem<In = T, Out = ()> + 'static,
+    {
+        Self::new(OnChanged::<T, _>::new(system.into_reactive_system()))
+    }
+
+    /// Create a new [`Reaction`] that spawns the future returned by `system` on the [`IoTaskPool`],
+    /// applying the command it resolves to once it finishes.
+    ///
+    /// The command is queued the next time the reaction is polled, so the schedule never blocks.
+    /// While a task is in flight for a target, changes don't start another one.
+    /// Instead, the reaction runs again once the task finishes.
+    /// Without the `multi_threaded` feature of `bevy_tasks`, the future runs to completion
+    /// as soon as it's spawned.
+    ///
+    /// [`IoTaskPool`]: bevy_tasks::IoTaskPool
+    pub fn new_async<Marker, S, F, C>(system: impl IntoReactiveSystem<Marker, System = S>) -> Self
+    where
+        Marker: Send + Sync + 'static,
+        S: ReactiveSystem<In = (), Out = F> + 'static,
+        F: Future<Output = C> + Send + 'static,
+        C: Command,
+    {
+        Self::new(AsyncReaction::new(system.into_reactive_system()))
+    }
+
+    /// Create a new [`Reaction`] that runs when its targets lose the component `T`,
+    /// with the entities it was removed from as input.
+    ///
+    /// Reactions with targets run once for each target that lost `T`.
+    /// Otherwise, the reaction runs once for every entity that lost `T` since the last run.
+    /// Changes to the parameters of `system` don't run the reaction.
+    pub fn on_removed<T, Marker, S>(system: impl IntoReactiveSystem<Marker, System = S>) -> Self
+    where
+        T: Component,
+        Marker: Send + Sync + 'static,
+        S: ReactiveSystem<In = Vec<Entity>, Out = ()> + 'static,
+    {
+        Self::new(OnRemoved::<T, _>::new(system.into_reactive_system()))
+    }
+
+    /// Create a new [`Reaction`] whose input is read from the world each time it runs.
+    ///
+    /// The reaction still only runs when the parameters of `system` have changed.
+    pub fn with_input<Marker, S, T>(
+        input: impl FnMut(&World) -> T + Send + Sync + 'static,
+        system: impl IntoReactiveSystem<Marker, System = S>,
+    ) -> Self
+    where
+        Marker: Send + Sync + 'static,
+        S: ReactiveSystem<In = T, Out = ()> + 'static,
+    {
+        Self::new(WithInput::new(input, system.into_reactive_system()))
+    }
+
+    /// Create a new [`Reaction`] that derives a [`Component`],
+    /// only inserting it when the value is different from the current one.
+    ///
+    /// Unlike [`Reaction::derive`], this won't trigger change detection for equal values,
+    /// so reactions that depend on their own output will settle.
+    pub fn derive_eq<Marker, C>(
+        system: impl ReactiveSystemParamFunction<Marker, In = (), Out = C> + Send + Sync + 'static,
+    ) -> Self
+    where
+        Marker: Send + Sync + 'static,
+        C: Component + PartialEq,
+    {
+        Self::new(system.map(
+            |scope: In<Scope<C>>, query: Peek<Query<&C>>, mut commands: Commands| {
+                let Scope { entity, input } = scope.0;
+                if query.get(entity).ok() != Some(&input) {
+                    commands.entity(entity).insert(input);
+                }
+            },
+        ))
+    }
+
+    /// Create a new [`Reaction`] that sends an event whenever the output of `system` changes.
+    ///
+    /// The event is created from the old and new values with `f`.
+    /// The first output of each target is only recorded, so no event is sent for it.
+    pub fn emit_on_change<Marker, T, E>(
+        system: impl ReactiveSystemParamFunction<Marker, In = (), Out = T> + Send + Sync + 'static,
+        f: impl Fn(T, T) -> E + Send + Sync + 'static,
     ) -> Self
     where
         Marker: Send + Sync + 'static,
-        S: ReactiveSystem<In = (), Out = ()> + 'static,
+        T: Clone + PartialEq + Send + Sync + 'static,
+        E: Event,
     {
-        Self {
-            inner: Arc::new(Mutex::new(Inner {
-                system: Box::new(system.into_reactive_system()),
-                entities: Vec::new(),
-            })),
-            _label: label,
-        }
-    }
-
-    pub fn with_label<L2>(&self, label: L2) -> Reaction<L2> {
-        let inner = self.inner.clone();
-        Reaction {
-            inner,
-            _label: label,
-        }
-    }
-
-    pub fn add_target(&mut self, entity: Entity) -> &mut Self {
-        self.inner.lock().unwrap().entities.push(entity);
-        self
+        Self::new(system.map(
+            move |scope: In<Scope<T>>,
+                  (mut values, mut commands): (Local<EntityHashMap<T>>, Commands)| {
+                let Scope { entity, input } = scope.0;
+                match values.insert(entity, input.clone()) {
+                    Some(old) if old != input => {
+                        let event = f(old, input);
+                        commands.add(move |world: &mut World| {
+                            world.send_event(event);
+                        });
+                    }
+                    _ => {}
+                }
+            },
+        ))
     }
 
-    pub fn run(&self, mut world: DeferredWorld, entity: Entity) {
-        let inner = &mut *self.inner.lock().unwrap();
-
-        if inner.system.is_changed(world.reborrow()) {
-            if inner.entities.is_empty() {
-                inner.system.run((), world.reborrow(), entity);
-            } else {
-                for entity in &inner.entities {
-                    inner.system.run((), world.reborrow(), *entity);
-                }
-            }
-        }
+    /// Create a new [`Reaction`] that pipes the latest outputs of a tuple of `sources` into `system`.
+    ///
+    /// See [`combine_latest`](crate::combine_latest).
+    pub fn combine_latest<Marker, SMarker, T, S>(
+        sources: T,
+        system: impl IntoReactiveSystem<SMarker, System = S>,
+    ) -> Self
+    where
+        Marker: Send + Sync + 'static,
+        T: IntoLatestSources<Marker>,
+        T::Sources: LatestSources<In = ()> + 'static,
+        S: ReactiveSystem<In = <T::Sources as LatestSources>::Out, Out = ()> + 'static,
+    {
+        Self::new(crate::combine_latest(sources).map(system))
     }
-}
 
-impl Reaction {
-    pub fn new<Marker, S>(system: impl IntoReactiveSystem<Marker, System = S>) -> Self
+    /// Create a new [`Reaction`] that derives a tuple of components like [`Reaction::derive_eq`],
+    /// only inserting the components that are different from their current values.
+    ///
+    /// This keeps unchanged components from triggering change detection.
+    pub fn derive_diff<Marker, B>(
+        system: impl ReactiveSystemParamFunction<Marker, In = (), Out = B> + Send + Sync + 'static,
+    ) -> Self
     where
         Marker: Send + Sync + 'static,
-        S: ReactiveSystem<In = (), Out = ()> + 'static,
+        B: DiffBundle,
     {
-        Self::from_label(PostUpdate, system)
+        Self::new(system.map(|scope: In<Scope<B>>, mut commands: Commands| {
+            let Scope { entity, input } = scope.0;
+            commands.add(move |world: &mut World| {
+                if let Some(mut entity) = world.get_entity_mut(entity) {
+                    input.insert_changed(&mut entity);
+                }
+            });
+        }))
     }
 
     /// Create a new [`Reaction`] that derives a [`Bundle`] from .
@@ -106,6 +1211,42 @@ impl Reaction {
         }))
     }
 
+    /// Create a new [`Reaction`] that derives a [`Bundle`] like [`Reaction::derive`],
+    /// inserting it for every changed target with a single batched command.
+    ///
+    /// This reduces command buffer overhead for reactions with many targets.
+    pub fn derive_batched<Marker, B>(
+        system: impl ReactiveSystemParamFunction<Marker, In = (), Out = B> + Send + Sync + 'static,
+    ) -> Self
+    where
+        Marker: Send + Sync + 'static,
+        B: Bundle,
+    {
+        let batch: Arc<Mutex<Vec<(Entity, B)>>> = Arc::default();
+        Self::new(
+            system.map(move |scope: In<Scope<B>>, mut commands: Commands| {
+                let Scope { entity, input } = scope.0;
+                let mut pending = batch.lock().unwrap();
+
+                // Queue the flush with the first bundle of each run.
+                if pending.is_empty() {
+                    let batch = batch.clone();
+                    commands.add(move |world: &mut World| {
+                        let pending = std::mem::take(&mut *batch.lock().unwrap());
+
+                        // Skip despawned targets instead of spawning them again.
+                        let pending: Vec<_> = pending
+                            .into_iter()
+                            .filter(|(entity, _)| world.get_entity(*entity).is_some())
+                            .collect();
+                        let _ = world.insert_or_spawn_batch(pending);
+                    });
+                }
+                pending.push((entity, input));
+            }),
+        )
+    }
+
     /// Create a new [`Reaction`] that derives a [`Bundle`] from .
     pub fn child<Marker, B>(
         system: impl ReactiveSystemParamFunction<Marker, In = (), Out = B> + Send + Sync + 'static,
@@ -152,6 +1293,42 @@ impl Reaction {
         ))
     }
 
+    /// Create a new [`Reaction`] that switches between any number of [`Bundle`]s.
+    ///
+    /// When the index returned by `system` changes, the closure at that index in `branches`
+    /// is called to replace the current [`Bundle`].
+    /// If the index is out of bounds, the current [`Bundle`] is removed.
+    pub fn switch_n<Marker, K, F, B>(
+        system: impl ReactiveSystemParamFunction<Marker, In = (), Out = K> + Send + Sync + 'static,
+        branches: impl IntoIterator<Item = F>,
+    ) -> Self
+    where
+        Marker: Send + Sync + 'static,
+        K: Into<usize> + 'static,
+        F: FnMut() -> B + Send + Sync + 'static,
+        B: Bundle,
+    {
+        let mut branches: Vec<F> = branches.into_iter().collect();
+        Self::new(system.map(
+            move |scope: In<Scope<K>>, mut commands: Commands, mut local: Local<Option<usize>>| {
+                let Scope { entity, input } = scope.0;
+                let idx = Some(input.into()).filter(|idx| *idx < branches.len());
+                if *local == idx {
+                    return;
+                }
+
+                let mut entity = commands.entity(entity);
+                if local.is_some() {
+                    entity.remove::<B>();
+                }
+                if let Some(idx) = idx {
+                    entity.insert(branches[idx]());
+                }
+                *local = idx;
+            },
+        ))
+    }
+
     /// Create a new [`Reaction`] that spawns [`Bundle`]s from an iterator.
     pub fn children<Marker, I>(
         system: impl ReactiveSystemParamFunction<Marker, In = (), Out = I> + Send + Sync + 'static,
@@ -175,4 +1352,881 @@ impl Reaction {
             },
         ))
     }
+
+    /// Create a new [`Reaction`] that spawns [`Bundle`]s from an iterator,
+    /// only respawning them when the collection is different from the last run.
+    pub fn children_eq<Marker, I>(
+        system: impl ReactiveSystemParamFunction<Marker, In = (), Out = I> + Send + Sync + 'static,
+    ) -> Self
+    where
+        Marker: Send + Sync + 'static,
+        I: IntoIterator + PartialEq + Clone + Send + Sync + 'static,
+        I::Item: Bundle,
+    {
+        Self::new(system.map(
+            move |scope: In<Scope<I>>,
+                  mut commands: Commands,
+                  mut local: Local<Option<(I, Vec<Entity>)>>| {
+                let input = scope.0.input;
+                if local.as_ref().is_some_and(|(last, _)| *last == input) {
+                    return;
+                }
+
+                if let Some((_, entities)) = local.take() {
+                    for entity in entities {
+                        commands.entity(entity).despawn();
+                    }
+                }
+
+                let entities = input
+                    .clone()
+                    .into_iter()
+                    .map(|item| commands.spawn(item).id())
+                    .collect();
+                *local = Some((input, entities));
+            },
+        ))
+    }
+
+    /// Create a new [`Reaction`] that spawns [`Bundle`]s from an iterator of keyed items.
+    ///
+    /// Entities are matched by key across runs, so items with the same key keep their entity
+    /// and only have their [`Bundle`] re-inserted.
+    /// Entities for removed keys are despawned and new keys are spawned.
+    pub fn children_keyed<Marker, I, K, B>(
+        system: impl ReactiveSystemParamFunction<Marker, In = (), Out = I> + Send + Sync + 'static,
+    ) -> Self
+    where
+        Marker: Send + Sync + 'static,
+        I: IntoIterator<Item = (K, B)> + 'static,
+        K: Hash + Eq + Send + Sync + 'static,
+        B: Bundle,
+    {
+        Self::new(system.map(
+            move |scope: In<Scope<I>>,
+                  mut commands: Commands,
+                  mut local: Local<HashMap<K, Entity>>| {
+                let mut entities = HashMap::new();
+                for (key, bundle) in scope.0.input {
+                    let entity = match local.remove(&key) {
+                        Some(entity) => {
+                            commands.entity(entity).insert(bundle);
+                            entity
+                        }
+                        None => commands.spawn(bundle).id(),
+                    };
+                    // Later items replace earlier ones with a duplicate key.
+                    if let Some(duplicate) = entities.insert(key, entity) {
+                        commands.entity(duplicate).despawn();
+                    }
+                }
+
+                for (_, entity) in local.drain() {
+                    commands.entity(entity).despawn();
+                }
+                *local = entities;
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::ReactionTestApp, ReactionPlugin};
+    use bevy_app::{AppExit, First, Last, Update};
+    #[cfg(feature = "state")]
+    use bevy_state::{app::AppExtStates, state::NextState};
+    use std::mem;
+
+    #[derive(Default, Resource)]
+    struct Trigger(u32);
+
+    #[derive(Resource)]
+    struct Seen(u32);
+
+    #[cfg(feature = "state")]
+    #[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    enum Mode {
+        #[default]
+        Off,
+        On,
+    }
+
+    #[cfg(feature = "state")]
+    fn set_mode(app: &mut ReactionTestApp, mode: Mode) {
+        app.world_mut().resource_mut::<NextState<Mode>>().set(mode);
+    }
+
+    fn trigger(app: &mut ReactionTestApp) {
+        app.world_mut().resource_mut::<Trigger>().0 += 1;
+    }
+
+    #[cfg(feature = "state")]
+    #[test]
+    fn reset_on_enter_in_state() {
+        let mut app = ReactionTestApp::new();
+        app.app_mut().init_state::<Mode>();
+        app.world_mut().init_resource::<Trigger>();
+        let reaction = app.spawn(
+            Reaction::new(
+                |_: In<Scope>,
+                 (_, mut count): (Res<Trigger>, Local<u32>),
+                 mut commands: Commands| {
+                    *count += 1;
+                    commands.insert_resource(Seen(*count));
+                },
+            )
+            .in_state(Mode::On)
+            .reset_on_enter(),
+        );
+
+        app.step();
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 0);
+
+        let mut commands = FilteredAccessSet::default();
+        add_deferred_access(app.world_mut(), &mut commands);
+        let access = app
+            .world()
+            .resource::<ReactionRegistry>()
+            .get(reaction)
+            .unwrap()
+            .access();
+        assert!(!access.is_compatible(&commands));
+
+        set_mode(&mut app, Mode::On);
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+        assert_eq!(app.world().resource::<Seen>().0, 1);
+
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+        assert_eq!(app.world().resource::<Seen>().0, 2);
+
+        set_mode(&mut app, Mode::Off);
+        app.step();
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+
+        set_mode(&mut app, Mode::On);
+        app.step();
+        assert_eq!(app.runs(reaction), 3);
+        assert_eq!(app.world().resource::<Seen>().0, 1);
+    }
+
+    #[cfg(feature = "state")]
+    #[derive(Default, Resource)]
+    struct Builds(u32);
+
+    #[cfg(feature = "state")]
+    #[test]
+    fn from_world_builds_the_system_once() {
+        let mut app = ReactionTestApp::new();
+        app.app_mut().init_state::<Mode>();
+        app.world_mut().init_resource::<Trigger>();
+        app.world_mut().init_resource::<Builds>();
+        let reaction = app.spawn(
+            Reaction::from_world(|world: &mut World| {
+                world.resource_mut::<Builds>().0 += 1;
+                |_: In<Scope>, _: Res<Trigger>| {}
+            })
+            .in_state(Mode::On)
+            .reset_on_enter(),
+        );
+        set_mode(&mut app, Mode::On);
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+        assert_eq!(app.world().resource::<Builds>().0, 1);
+
+        // Entering the state again initializes the system again, without building a new one.
+        set_mode(&mut app, Mode::Off);
+        app.step();
+        set_mode(&mut app, Mode::On);
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+        assert_eq!(app.world().resource::<Builds>().0, 1);
+    }
+
+    #[derive(Component)]
+    struct Swapped;
+
+    fn write_all(reaction: &Reaction) -> bool {
+        lock(&reaction.inner)
+            .access
+            .combined_access()
+            .has_write_all()
+    }
+
+    #[test]
+    fn set_system_keeps_targets() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let a = app.spawn_target(());
+        let b = app.spawn_target(());
+        let mut reaction = Reaction::new(|_: In<Scope>, _: Res<Trigger>| {});
+        reaction.add_target(a).add_target(b);
+        let reaction = app.spawn(reaction);
+
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+
+        let mut entity = app.world_mut().entity_mut(reaction);
+        let mut reaction_ref = entity.get_mut::<Reaction>().unwrap();
+        reaction_ref.set_system(
+            |scope: In<Scope>, _: Res<Trigger>, mut commands: Commands| {
+                commands.entity(scope.entity).insert(Swapped);
+            },
+        );
+        assert!(write_all(&reaction_ref));
+
+        app.step();
+        assert_eq!(app.runs(reaction), 4);
+        assert!(app.world().get::<Swapped>(a).is_some());
+        assert!(app.world().get::<Swapped>(b).is_some());
+
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 6);
+    }
+
+    #[test]
+    fn reinserted_reaction_resets() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let entity = app.spawn(Reaction::new(
+            |_: In<Scope>, (_, mut count): (Res<Trigger>, Local<u32>), mut commands: Commands| {
+                *count += 1;
+                commands.insert_resource(Seen(*count));
+            },
+        ));
+
+        app.step();
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.world().resource::<Seen>().0, 2);
+
+        let reaction = app
+            .world_mut()
+            .entity_mut(entity)
+            .take::<Reaction>()
+            .unwrap();
+        app.step();
+        app.world_mut().entity_mut(entity).insert(reaction);
+        app.world_mut().flush();
+        let reaction = app.world().get::<Reaction>(entity).unwrap();
+        assert!(write_all(reaction));
+
+        app.step();
+        assert_eq!(app.runs(entity), 3);
+        assert_eq!(app.world().resource::<Seen>().0, 1);
+        assert!(!write_all(app.world().get::<Reaction>(entity).unwrap()));
+    }
+
+    #[cfg(feature = "time")]
+    #[derive(Default, Resource)]
+    struct FixedSteps(u32);
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn fixed_reactions_run_once_per_change() {
+        let mut app = ReactionTestApp::with_plugin(ReactionPlugin::new().fixed_update());
+        app.world_mut().init_resource::<Trigger>();
+        app.world_mut().init_resource::<FixedSteps>();
+        app.app_mut()
+            .add_systems(FixedUpdate, |mut steps: ResMut<FixedSteps>| steps.0 += 1);
+        let reaction = app.spawn(Reaction::fixed(|_: In<Scope>, _: Res<Trigger>| {}));
+        app.step();
+
+        // Several fixed steps run in this frame, but the reaction only runs for the first.
+        app.advance(Duration::from_millis(50));
+        assert_eq!(app.world().resource::<FixedSteps>().0, 3);
+        assert_eq!(app.runs(reaction), 1);
+
+        trigger(&mut app);
+        app.advance(Duration::from_millis(50));
+        assert_eq!(app.runs(reaction), 2);
+
+        app.advance(Duration::from_millis(50));
+        assert_eq!(app.runs(reaction), 2);
+
+        // Frames without a fixed step don't lose the change.
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+        app.advance(Duration::from_millis(50));
+        assert_eq!(app.runs(reaction), 3);
+    }
+
+    #[test]
+    fn poisoned_reactions_are_skipped() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let poisoned = app.spawn(
+            Reaction::new(|_: In<Scope>, trigger: Res<Trigger>| {
+                assert_ne!(trigger.0, 1, "reaction panicked");
+            })
+            .named("poisoned"),
+        );
+        let other = app.spawn(Reaction::new(|_: In<Scope>, _: Res<Trigger>| {}));
+        app.step();
+
+        trigger(&mut app);
+        let reaction = app.world().get::<Reaction>(poisoned).unwrap().clone();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            reaction.run_on(app.world_mut(), poisoned);
+        }));
+        assert!(result.is_err());
+        let runs = app.runs(poisoned);
+
+        // Other reactions keep running, and the poisoned one is skipped instead of panicking.
+        app.step();
+        assert_eq!(app.runs(other), 2);
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(other), 3);
+        assert_eq!(app.runs(poisoned), runs);
+
+        assert_eq!(reaction.name().as_deref(), Some("poisoned"));
+        assert_eq!(reaction.target_count(), 0);
+    }
+
+    #[derive(Default, Resource)]
+    struct Bump(bool);
+
+    #[test]
+    fn also_in_runs_once_per_change() {
+        let mut app = ReactionTestApp::with_plugin(ReactionPlugin::new().with_label(First));
+        app.world_mut().init_resource::<Trigger>();
+        app.world_mut().init_resource::<Bump>();
+        app.app_mut().add_systems(
+            Update,
+            |mut bump: ResMut<Bump>, mut trigger: ResMut<Trigger>| {
+                if mem::take(&mut bump.0) {
+                    trigger.0 += 1;
+                }
+            },
+        );
+        let reaction = app.spawn(Reaction::new(|_: In<Scope>, _: Res<Trigger>| {}).also_in(First));
+        app.step_n(2);
+        let runs = app.runs(reaction);
+
+        // Changed before `First`, so it runs there and not again in `PostUpdate`.
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), runs + 1);
+
+        // Changed in `Update`, so it runs in `PostUpdate` and not again in the next `First`.
+        app.world_mut().resource_mut::<Bump>().0 = true;
+        app.step();
+        assert_eq!(app.runs(reaction), runs + 2);
+        app.step();
+        assert_eq!(app.runs(reaction), runs + 2);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn ttl_despawns_reactions() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let reaction = app.spawn(
+            Reaction::new(|_: In<Scope>, _: Res<Trigger>| {}).with_ttl(Duration::from_millis(100)),
+        );
+        app.step_n(2);
+
+        app.advance(Duration::from_millis(60));
+        trigger(&mut app);
+        app.advance(Duration::from_millis(30));
+        assert_eq!(app.runs(reaction), 2);
+
+        app.advance(Duration::from_millis(30));
+        assert!(app.world().get_entity(reaction).is_none());
+    }
+
+    #[derive(Component)]
+    struct Visits(u32);
+
+    fn visits(app: &mut ReactionTestApp) -> Vec<u32> {
+        app.world_mut()
+            .query::<&Visits>()
+            .iter(app.world())
+            .map(|visits| visits.0)
+            .collect()
+    }
+
+    #[test]
+    fn chunked_targets_spread_over_frames() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let mut reaction = Reaction::new(
+            |scope: In<Scope>, _: Res<Trigger>, mut commands: Commands| {
+                let entity = scope.entity;
+                commands.add(move |world: &mut World| {
+                    world.get_mut::<Visits>(entity).unwrap().0 += 1;
+                });
+            },
+        )
+        .chunked_targets(2);
+        for _ in 0..5 {
+            let target = app.spawn_target(Visits(0));
+            reaction.add_target(target);
+        }
+        let reaction = app.spawn(reaction);
+
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+        app.step();
+        assert_eq!(app.runs(reaction), 4);
+        app.step();
+        assert_eq!(app.runs(reaction), 5);
+        app.step();
+        assert_eq!(app.runs(reaction), 5);
+        assert_eq!(visits(&mut app), vec![1; 5]);
+
+        // A change while targets are still queued only queues each target once.
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 7);
+        trigger(&mut app);
+        app.step_n(4);
+        assert_eq!(app.runs(reaction), 12);
+    }
+
+    #[test]
+    fn on_exit_runs_once_on_app_exit() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let reaction = app.spawn(Reaction::on_exit(
+            |_: In<Scope>, trigger: Res<Trigger>, mut commands: Commands| {
+                commands.insert_resource(Seen(trigger.0));
+            },
+        ));
+        app.step();
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 0);
+
+        app.world_mut().send_event(AppExit::Success);
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+        assert_eq!(app.world().resource::<Seen>().0, 1);
+
+        app.world_mut().send_event(AppExit::Success);
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+    }
+
+    #[derive(Resource)]
+    struct Ready;
+
+    #[test]
+    fn init_if_waits_for_predicate() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let reaction = app.spawn(
+            Reaction::new(|_: In<Scope>, _: Res<Trigger>| {})
+                .init_if(|world| world.contains_resource::<Ready>()),
+        );
+        app.step();
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 0);
+        assert!(write_all(app.world().get::<Reaction>(reaction).unwrap()));
+
+        // Runs as soon as it's initialized, even without a change.
+        app.world_mut().insert_resource(Ready);
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+        assert!(!write_all(app.world().get::<Reaction>(reaction).unwrap()));
+
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+    }
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Branch(usize);
+
+    #[test]
+    fn switch_n_inserts_the_current_branch() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let reaction = app.spawn(Reaction::switch_n(
+            |_: In<Scope>, trigger: Res<Trigger>| trigger.0 as usize,
+            (0..3).map(|idx| move || Branch(idx)),
+        ));
+        app.step();
+        assert_eq!(app.world().get::<Branch>(reaction), Some(&Branch(0)));
+
+        trigger(&mut app);
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.world().get::<Branch>(reaction), Some(&Branch(2)));
+
+        // Out of range, so no branch is inserted.
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.world().get::<Branch>(reaction), None);
+
+        app.world_mut().resource_mut::<Trigger>().0 = 1;
+        app.step();
+        assert_eq!(app.world().get::<Branch>(reaction), Some(&Branch(1)));
+    }
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Item(u32, u32);
+
+    #[derive(Resource)]
+    struct Items(Vec<Item>);
+
+    fn items(app: &mut ReactionTestApp) -> Vec<(Entity, Item)> {
+        let mut items: Vec<_> = app
+            .world_mut()
+            .query::<(Entity, &Item)>()
+            .iter(app.world())
+            .map(|(entity, item)| (entity, item.clone()))
+            .collect();
+        items.sort_by_key(|(_, item)| item.0);
+        items
+    }
+
+    #[test]
+    fn children_keyed_reuses_entities() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut()
+            .insert_resource(Items(vec![Item(1, 10), Item(2, 20)]));
+        app.spawn(Reaction::children_keyed(
+            |_: In<Scope>, items: Res<Items>| {
+                items
+                    .0
+                    .iter()
+                    .map(|item| (item.0, item.clone()))
+                    .collect::<Vec<_>>()
+            },
+        ));
+        app.step();
+        let before = items(&mut app);
+        assert_eq!(before.len(), 2);
+
+        app.world_mut().resource_mut::<Items>().0 = vec![Item(2, 21), Item(3, 30)];
+        app.step();
+        let after = items(&mut app);
+        assert_eq!(after.len(), 2);
+        assert!(app.world().get_entity(before[0].0).is_none());
+        assert_eq!(after[0], (before[1].0, Item(2, 21)));
+        assert_eq!(after[1].1, Item(3, 30));
+    }
+
+    #[test]
+    fn children_eq_skips_equal_collections() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut()
+            .insert_resource(Items(vec![Item(1, 10), Item(2, 20)]));
+        app.spawn(Reaction::children_eq(|_: In<Scope>, items: Res<Items>| {
+            items.0.clone()
+        }));
+        app.step();
+        let before = items(&mut app);
+
+        // Changed, but equal, so the same entities are kept.
+        app.world_mut().resource_mut::<Items>().0 = vec![Item(1, 10), Item(2, 20)];
+        app.step();
+        assert_eq!(items(&mut app), before);
+
+        app.world_mut().resource_mut::<Items>().0 = vec![Item(1, 11)];
+        app.step();
+        let after = items(&mut app);
+        assert_eq!(after.len(), 1);
+        assert_eq!(after[0].1, Item(1, 11));
+        assert!(app.world().get_entity(before[0].0).is_none());
+    }
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Parity(u32);
+
+    fn last_changed(app: &ReactionTestApp, entity: Entity) -> Tick {
+        app.world()
+            .entity(entity)
+            .get_ref::<Parity>()
+            .unwrap()
+            .last_changed()
+    }
+
+    #[test]
+    fn derive_eq_skips_equal_components() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let target = app.spawn_target(());
+        let mut reaction =
+            Reaction::derive_eq(|_: In<Scope>, trigger: Res<Trigger>| Parity(trigger.0 % 2));
+        reaction.add_target(target);
+        app.spawn(reaction);
+        app.step();
+        assert_eq!(app.world().get::<Parity>(target), Some(&Parity(0)));
+        let inserted_at = last_changed(&app, target);
+
+        // Equal, so the component isn't inserted again.
+        app.world_mut().resource_mut::<Trigger>().0 = 2;
+        app.step();
+        assert_eq!(last_changed(&app, target), inserted_at);
+
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.world().get::<Parity>(target), Some(&Parity(1)));
+        assert_ne!(last_changed(&app, target), inserted_at);
+    }
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Level(u32);
+
+    #[derive(Default, Resource)]
+    struct ChangedLast {
+        parity: bool,
+        level: bool,
+    }
+
+    #[test]
+    fn derive_diff_only_inserts_changed_components() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        app.world_mut().init_resource::<ChangedLast>();
+        app.app_mut().add_systems(
+            Last,
+            |parity: Query<Ref<Parity>>,
+             level: Query<Ref<Level>>,
+             mut changed: ResMut<ChangedLast>| {
+                changed.parity = parity.iter().any(|parity| parity.is_changed());
+                changed.level = level.iter().any(|level| level.is_changed());
+            },
+        );
+        let target = app.spawn_target(());
+        let mut reaction = Reaction::derive_diff(|_: In<Scope>, trigger: Res<Trigger>| {
+            (Parity(trigger.0 % 2), Level(trigger.0 / 2))
+        });
+        reaction.add_target(target);
+        app.spawn(reaction);
+        app.step();
+        assert_eq!(app.world().get::<Level>(target), Some(&Level(0)));
+
+        // Only the parity is different, so the level isn't inserted again.
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.world().get::<Parity>(target), Some(&Parity(1)));
+        assert_eq!(app.world().get::<Level>(target), Some(&Level(0)));
+        let changed = app.world().resource::<ChangedLast>();
+        assert!(changed.parity);
+        assert!(!changed.level);
+    }
+
+    #[test]
+    fn derive_batched_skips_despawned_targets() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let a = app.spawn_target(());
+        let b = app.spawn_target(());
+        let c = app.spawn_target(());
+        let mut reaction = Reaction::derive_batched(
+            move |scope: In<Scope>, trigger: Res<Trigger>, mut commands: Commands| {
+                // Despawned by the first target, after `c` was already batched.
+                if scope.entity == a && trigger.0 == 1 {
+                    commands.entity(c).despawn();
+                }
+                Parity(trigger.0 % 2)
+            },
+        );
+        reaction.add_target(a).add_target(b).add_target(c);
+        let reaction = app.spawn(reaction);
+        app.step();
+        assert_eq!(app.runs(reaction), 3);
+        for target in [a, b, c] {
+            assert_eq!(app.world().get::<Parity>(target), Some(&Parity(0)));
+        }
+
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 6);
+        assert_eq!(app.world().get::<Parity>(a), Some(&Parity(1)));
+        assert_eq!(app.world().get::<Parity>(b), Some(&Parity(1)));
+        assert!(app.world().get_entity(c).is_none());
+    }
+
+    #[test]
+    fn spawned_targets_are_owned_by_the_reaction() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let other = app.spawn_target(());
+        let mut reaction =
+            Reaction::derive(|_: In<Scope>, trigger: Res<Trigger>| Parity(trigger.0 % 2));
+        let target = reaction.spawn_target(&mut app.world_mut().commands());
+        reaction.add_target(other);
+        app.world_mut().flush();
+        let reaction = app.spawn(reaction);
+        app.step();
+        assert_eq!(app.world().get::<Parity>(target), Some(&Parity(0)));
+        assert_eq!(app.world().get::<Parity>(other), Some(&Parity(0)));
+
+        // Only the spawned target is despawned with the reaction.
+        app.world_mut().despawn(reaction);
+        app.world_mut().flush();
+        assert!(app.world().get_entity(target).is_none());
+        assert!(app.world().get_entity(other).is_some());
+    }
+
+    #[derive(Resource)]
+    struct Enabled(bool);
+
+    #[test]
+    fn run_if_gates_polling() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        app.world_mut().insert_resource(Enabled(false));
+        let reaction = app.spawn(
+            Reaction::new(|_: In<Scope>, _: Res<Trigger>| {})
+                .run_if(|world| world.resource::<Enabled>().0),
+        );
+        app.step();
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 0);
+        assert_eq!(app.world().resource::<ReactionDiagnostics>().polled, 0);
+
+        app.world_mut().resource_mut::<Enabled>().0 = true;
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+
+        app.world_mut().resource_mut::<Enabled>().0 = false;
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+    }
+
+    #[test]
+    fn run_on_drives_reactions_manually() {
+        let mut world = World::new();
+        world.init_resource::<Trigger>();
+        let entity = world
+            .spawn(Reaction::new(
+                |_: In<Scope>, trigger: Res<Trigger>, mut commands: Commands| {
+                    commands.insert_resource(Seen(trigger.0));
+                },
+            ))
+            .id();
+        let reaction = world.get::<Reaction>(entity).unwrap().clone();
+
+        reaction.run_on(&mut world, entity);
+        assert_eq!(reaction.run_count(), 1);
+        assert_eq!(world.resource::<Seen>().0, 0);
+
+        world.clear_trackers();
+        reaction.run_on(&mut world, entity);
+        assert_eq!(reaction.run_count(), 1);
+
+        world.resource_mut::<Trigger>().0 = 1;
+        reaction.run_on(&mut world, entity);
+        assert_eq!(reaction.run_count(), 2);
+        assert_eq!(world.resource::<Seen>().0, 1);
+    }
+
+    #[test]
+    fn despawned_targets_are_skipped() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let a = app.spawn_target(Visits(0));
+        let b = app.spawn_target(Visits(0));
+        let mut reaction =
+            Reaction::new(|scope: In<Scope>, _: Res<Trigger>, query: Query<&Visits>| {
+                query.get(scope.entity).unwrap();
+            });
+        reaction.add_target(a).add_target(b);
+        let reaction = app.spawn(reaction);
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+
+        // Despawned earlier in the frame, before the reaction runs.
+        app.app_mut()
+            .add_systems(Update, move |mut commands: Commands| {
+                if let Some(entity) = commands.get_entity(a) {
+                    entity.despawn_recursive();
+                }
+            });
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 3);
+    }
+
+    #[derive(Component)]
+    struct Active;
+
+    #[test]
+    fn requires_skips_targets_without_component() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let a = app.spawn_target((Visits(0), Active));
+        let b = app.spawn_target(Visits(0));
+        let mut reaction = Reaction::new(
+            |scope: In<Scope>, _: Res<Trigger>, mut commands: Commands| {
+                let entity = scope.entity;
+                commands.add(move |world: &mut World| {
+                    world.get_mut::<Visits>(entity).unwrap().0 += 1;
+                });
+            },
+        )
+        .requires::<Active>();
+        reaction.add_target(a).add_target(b);
+        app.spawn(reaction);
+        app.step();
+        assert_eq!(app.world().get::<Visits>(a).unwrap().0, 1);
+        assert_eq!(app.world().get::<Visits>(b).unwrap().0, 0);
+
+        app.world_mut().entity_mut(b).insert(Active);
+        app.world_mut().entity_mut(a).remove::<Active>();
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.world().get::<Visits>(a).unwrap().0, 1);
+        assert_eq!(app.world().get::<Visits>(b).unwrap().0, 1);
+    }
+
+    #[test]
+    fn handle_modifies_spawned_reaction() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let a = app.spawn_target(());
+        let b = app.spawn_target(());
+        let mut reaction = Reaction::new(|_: In<Scope>, _: Res<Trigger>| {});
+        reaction.add_target(a);
+        let handle = reaction.handle();
+        let reaction = app.spawn(reaction);
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+
+        handle.add_target(b);
+        assert_eq!(handle.targets(), vec![a, b]);
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 3);
+
+        handle.set_enabled(false);
+        assert!(!handle.is_enabled());
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 3);
+
+        handle.set_enabled(true);
+        handle.remove_target(a);
+        trigger(&mut app);
+        app.step();
+        assert_eq!(app.runs(reaction), 4);
+
+        // The handle outlives the reaction, without any effect.
+        app.world_mut().despawn(reaction);
+        app.step();
+        handle.add_target(a);
+        assert_eq!(handle.targets(), vec![b, a]);
+    }
 }
@@ -0,0 +1,86 @@
+use crate::Reaction;
+use bevy_ecs::{entity::EntityHashMap, prelude::*, schedule::InternedScheduleLabel};
+
+/// Registry of every spawned [`Reaction`], for tools like inspectors.
+///
+/// Each reaction shares its state with the registry,
+/// so its name, targets, and last run are always up to date.
+#[derive(Default, Resource)]
+pub struct ReactionRegistry {
+    reactions: EntityHashMap<Reaction<InternedScheduleLabel>>,
+}
+
+impl ReactionRegistry {
+    /// Returns the reaction of `entity`, if it has one.
+    pub fn get(&self, entity: Entity) -> Option<&Reaction<InternedScheduleLabel>> {
+        self.reactions.get(&entity)
+    }
+
+    /// Iterate over every reaction and its entity.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &Reaction<InternedScheduleLabel>)> {
+        self.reactions
+            .iter()
+            .map(|(entity, reaction)| (*entity, reaction))
+    }
+
+    /// Returns the number of reactions.
+    pub fn len(&self) -> usize {
+        self.reactions.len()
+    }
+
+    /// Returns `true` if there are no reactions.
+    pub fn is_empty(&self) -> bool {
+        self.reactions.is_empty()
+    }
+
+    pub(crate) fn insert(&mut self, entity: Entity, reaction: Reaction<InternedScheduleLabel>) {
+        self.reactions.insert(entity, reaction);
+    }
+
+    pub(crate) fn remove(&mut self, entity: Entity) {
+        self.reactions.remove(&entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::ReactionTestApp, Scope};
+
+    #[derive(Default, Resource)]
+    struct Trigger;
+
+    #[test]
+    fn registry_tracks_spawned_reactions() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        let target = app.spawn_target(());
+        let mut named = Reaction::new(|_: In<Scope>, _: Res<Trigger>| {}).named("named");
+        named.add_target(target);
+        let named = app.spawn(named);
+        let other = app.spawn(Reaction::new(|_: In<Scope>, _: Res<Trigger>| {}));
+
+        let registry = app.world().resource::<ReactionRegistry>();
+        assert_eq!(registry.len(), 2);
+        let reaction = registry.get(named).unwrap();
+        assert_eq!(reaction.name().as_deref(), Some("named"));
+        assert_eq!(reaction.target_count(), 1);
+        assert_eq!(reaction.last_run(), None);
+
+        // The registry shares the state of each reaction, so it sees the run.
+        app.step();
+        let registry = app.world().resource::<ReactionRegistry>();
+        assert!(registry.get(named).unwrap().last_run().is_some());
+
+        app.world_mut().despawn(other);
+        let registry = app.world().resource::<ReactionRegistry>();
+        assert_eq!(registry.len(), 1);
+        assert!(registry.get(other).is_none());
+        let mut entities: Vec<_> = registry.iter().map(|(entity, _)| entity).collect();
+        entities.sort();
+        assert_eq!(entities, vec![named]);
+
+        app.world_mut().despawn(named);
+        assert!(app.world().resource::<ReactionRegistry>().is_empty());
+    }
+}
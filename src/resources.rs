@@ -0,0 +1,240 @@
+use crate::{system_param::add_access, ReactiveSystemParam};
+use bevy_ecs::{
+    component::{ComponentId, Tick},
+    prelude::*,
+    query::{FilteredAccess, FilteredAccessSet},
+    system::{SystemMeta, SystemParam, SystemState},
+    world::{unsafe_world_cell::UnsafeWorldCell, DeferredWorld},
+};
+use std::{any, ops::Deref};
+
+/// Tuple of resources that can be read with [`ReactiveResources`].
+pub trait ResourceTuple: Send + Sync + 'static {
+    /// Tuple of [`Res`] for each resource.
+    type Item<'w>;
+
+    /// State of the tuple of [`Res`] as a [`SystemParam`].
+    type State: Send + Sync + 'static;
+
+    /// Returns `true` if any of the resources changed between `last_run` and `this_run`.
+    fn is_changed(world: &World, last_run: Tick, this_run: Tick) -> bool;
+
+    /// Add read access to each resource to `access`.
+    fn add_read(world: &mut World, access: &mut FilteredAccess<ComponentId>);
+
+    /// Get each resource from `world`.
+    ///
+    /// # Safety
+    /// `world` must have read access to each resource.
+    ///
+    /// # Panics
+    /// Panics if any of the resources don't exist.
+    unsafe fn get(world: UnsafeWorldCell<'_>) -> Self::Item<'_>;
+
+    /// Initialize the state of the tuple of [`Res`] as a [`SystemParam`].
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State;
+
+    /// Get the tuple of [`Res`] as a [`SystemParam`].
+    ///
+    /// # Safety
+    /// Same as [`SystemParam::get_param`].
+    unsafe fn get_param<'w>(
+        state: &mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'w>,
+        change_tick: Tick,
+    ) -> Self::Item<'w>;
+}
+
+macro_rules! impl_resource_tuple {
+    ($($r:ident),*) => {
+        impl<$($r: Resource),*> ResourceTuple for ($($r,)*) {
+            type Item<'w> = ($(Res<'w, $r>,)*);
+
+            type State = <($(Res<'static, $r>,)*) as SystemParam>::State;
+
+            fn is_changed(world: &World, last_run: Tick, this_run: Tick) -> bool {
+                $(world
+                    .get_resource_change_ticks::<$r>()
+                    .is_some_and(|ticks| ticks.is_changed(last_run, this_run)))||*
+            }
+
+            fn add_read(world: &mut World, access: &mut FilteredAccess<ComponentId>) {
+                $(
+                    // Make sure the resource has a `ComponentId`, even if it isn't inserted yet.
+                    SystemState::<Res<$r>>::new(world);
+                    access.add_read(world.components().resource_id::<$r>().unwrap());
+                )*
+            }
+
+            unsafe fn get(world: UnsafeWorldCell<'_>) -> Self::Item<'_> {
+                // SAFETY: The caller ensures `world` has read access to each resource.
+                ($(unsafe { world.get_resource_ref::<$r>() }.unwrap_or_else(|| {
+                    panic!(
+                        "Resource requested by a reaction does not exist: {}",
+                        any::type_name::<$r>()
+                    )
+                }),)*)
+            }
+
+            fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+                <($(Res<$r>,)*)>::init_state(world, system_meta)
+            }
+
+            unsafe fn get_param<'w>(
+                state: &mut Self::State,
+                system_meta: &SystemMeta,
+                world: UnsafeWorldCell<'w>,
+                change_tick: Tick,
+            ) -> Self::Item<'w> {
+                // SAFETY: The caller upholds the safety contract of `SystemParam::get_param`.
+                unsafe { <($(Res<$r>,)*)>::get_param(state, system_meta, world, change_tick) }
+            }
+        }
+    };
+}
+
+impl_resource_tuple!(A);
+impl_resource_tuple!(A, B);
+impl_resource_tuple!(A, B, C);
+impl_resource_tuple!(A, B, C, D);
+impl_resource_tuple!(A, B, C, D, E);
+impl_resource_tuple!(A, B, C, D, E, F);
+
+/// Reactive system parameter to read a tuple of resources, like `(Res<A>, Res<B>, Res<C>)`.
+///
+/// Unlike nested tuples of [`Res`], change detection checks the ticks of every resource in one pass.
+pub struct ReactiveResources<'w, T: ResourceTuple> {
+    resources: T::Item<'w>,
+}
+
+impl<'w, T: ResourceTuple> ReactiveResources<'w, T> {
+    /// Returns the tuple of [`Res`].
+    pub fn into_inner(self) -> T::Item<'w> {
+        self.resources
+    }
+}
+
+impl<'w, T: ResourceTuple> Deref for ReactiveResources<'w, T> {
+    type Target = T::Item<'w>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.resources
+    }
+}
+
+/// Outside of reactions, [`ReactiveResources`] is the same as a tuple of [`Res`].
+unsafe impl<T: ResourceTuple> SystemParam for ReactiveResources<'_, T> {
+    type State = T::State;
+
+    type Item<'world, 'state> = ReactiveResources<'world, T>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        T::init_state(world, system_meta)
+    }
+
+    unsafe fn get_param<'world, 'state>(
+        state: &'state mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'world>,
+        change_tick: Tick,
+    ) -> Self::Item<'world, 'state> {
+        ReactiveResources {
+            // SAFETY: The caller ensures `world` has read access to each resource.
+            resources: unsafe { T::get_param(state, system_meta, world, change_tick) },
+        }
+    }
+}
+
+impl<T: ResourceTuple> ReactiveSystemParam for ReactiveResources<'_, T> {
+    type State = ();
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        let _ = world;
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) -> bool {
+        let _ = state;
+        T::is_changed(&world, world.last_change_tick(), world.read_change_tick())
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        let mut filtered = FilteredAccess::default();
+        T::add_read(world, &mut filtered);
+        add_access::<Self>(world, access, filtered);
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        let _ = state;
+        ReactiveResources {
+            // SAFETY: The caller ensures `world` has read access to each resource.
+            resources: unsafe { T::get(world) },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::ReactionTestApp, Reaction, Scope};
+
+    #[derive(Resource)]
+    struct Gold(u32);
+
+    #[derive(Resource)]
+    struct Wood(u32);
+
+    #[derive(Resource)]
+    struct Stone(u32);
+
+    #[derive(Resource)]
+    struct Total(u32);
+
+    #[test]
+    fn runs_when_any_resource_changes() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().insert_resource(Gold(1));
+        app.world_mut().insert_resource(Wood(2));
+        app.world_mut().insert_resource(Stone(3));
+        let reaction = app.spawn(Reaction::new(
+            |_: In<Scope>, resources: ReactiveResources<(Gold, Wood)>, mut commands: Commands| {
+                let (gold, wood) = &*resources;
+                commands.insert_resource(Total(gold.0 + wood.0));
+            },
+        ));
+        app.step();
+        assert_eq!(app.world().resource::<Total>().0, 3);
+        let runs = app.runs(reaction);
+
+        app.world_mut().resource_mut::<Stone>().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), runs);
+
+        app.world_mut().resource_mut::<Wood>().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), runs + 1);
+        assert_eq!(app.world().resource::<Total>().0, 4);
+
+        app.world_mut().resource_mut::<Gold>().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), runs + 2);
+        assert_eq!(app.world().resource::<Total>().0, 5);
+    }
+
+    #[test]
+    fn resources_in_system() {
+        let mut world = World::new();
+        world.insert_resource(Gold(1));
+        world.insert_resource(Wood(2));
+
+        let mut system = IntoSystem::into_system(|resources: ReactiveResources<(Gold, Wood)>| {
+            let (gold, wood) = resources.into_inner();
+            gold.0 + wood.0
+        });
+        system.initialize(&mut world);
+        assert_eq!(system.run((), &mut world), 3);
+    }
+}
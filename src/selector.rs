@@ -0,0 +1,272 @@
+use crate::{IntoReactiveSystem, ReactiveSystem};
+use bevy_ecs::{
+    component::{ComponentId, Tick},
+    entity::EntityHashMap,
+    prelude::*,
+    query::{FilteredAccess, FilteredAccessSet},
+    world::DeferredWorld,
+};
+use bevy_utils::HashMap;
+use std::{
+    any::Any,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+};
+
+/// Unique id of a [`Selector`], shared by its clones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SelectorId(u64);
+
+/// Latest outputs of every [`Selector`], by id.
+#[derive(Default, Resource)]
+pub struct Selectors {
+    values: HashMap<SelectorId, Box<dyn Any + Send + Sync>>,
+}
+
+impl Selectors {
+    /// Returns the latest output of `selector`, if it has been computed.
+    pub fn get<T: Send + Sync + 'static>(&self, selector: &Selector<T>) -> Option<&T> {
+        self.value(selector.id).map(|value| &value.value)
+    }
+
+    fn value<T: Send + Sync + 'static>(&self, id: SelectorId) -> Option<&SelectorValue<T>> {
+        self.values.get(&id).and_then(|value| value.downcast_ref())
+    }
+}
+
+struct SelectorValue<T> {
+    value: T,
+    /// Incremented each time `value` is recomputed.
+    generation: u32,
+    updated_at: Tick,
+}
+
+struct SelectorSystem<T> {
+    system: Box<dyn ReactiveSystem<In = (), Out = T>>,
+    is_init: bool,
+}
+
+/// Reactive system with a memoized output, shared by every reaction that uses a clone of it.
+///
+/// The output is recomputed at most once per change tick, no matter how many reactions use it,
+/// and is stored in the [`Selectors`] resource.
+/// Selectors don't depend on targets, so the [`Scope`](crate::Scope) entity of the system is
+/// [`Entity::PLACEHOLDER`].
+pub struct Selector<T> {
+    id: SelectorId,
+    system: Arc<Mutex<SelectorSystem<T>>>,
+    resource_id: Option<ComponentId>,
+    generation: Option<u32>,
+    /// Generation of the output each target last ran with.
+    targets: EntityHashMap<u32>,
+}
+
+impl<T> Selector<T> {
+    /// Create a new selector from `system`.
+    pub fn new<Marker, S>(system: impl IntoReactiveSystem<Marker, System = S>) -> Self
+    where
+        S: ReactiveSystem<In = (), Out = T> + 'static,
+    {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+        Self {
+            id: SelectorId(NEXT_ID.fetch_add(1, Ordering::Relaxed)),
+            system: Arc::new(Mutex::new(SelectorSystem {
+                system: Box::new(system.into_reactive_system()),
+                is_init: false,
+            })),
+            resource_id: None,
+            generation: None,
+            targets: EntityHashMap::default(),
+        }
+    }
+
+    /// Returns the id of this selector.
+    pub fn id(&self) -> SelectorId {
+        self.id
+    }
+}
+
+impl<T> Clone for Selector<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            system: self.system.clone(),
+            resource_id: None,
+            generation: None,
+            targets: EntityHashMap::default(),
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> Selector<T> {
+    /// Recompute the output if the system changed, at most once per change tick.
+    ///
+    /// Returns the generation of the output.
+    fn update(&self, mut world: DeferredWorld) -> u32 {
+        let this_run = world.last_change_tick();
+        let current = world
+            .resource::<Selectors>()
+            .value::<T>(self.id)
+            .map(|value| (value.generation, value.updated_at));
+        if let Some((generation, updated_at)) = current {
+            if updated_at == this_run {
+                return generation;
+            }
+        }
+
+        let mut system = self.system.lock().unwrap_or_else(PoisonError::into_inner);
+        let is_changed = system.system.is_changed(world.reborrow());
+
+        if let (Some((generation, _)), false) = (current, is_changed) {
+            let mut selectors = world.resource_mut::<Selectors>();
+            let value = selectors
+                .values
+                .get_mut(&self.id)
+                .and_then(|value| value.downcast_mut::<SelectorValue<T>>())
+                .unwrap();
+            value.updated_at = this_run;
+            return generation;
+        }
+
+        let value = system.system.run((), world.reborrow(), Entity::PLACEHOLDER);
+        let generation = current.map_or(0, |(generation, _)| generation.wrapping_add(1));
+        world.resource_mut::<Selectors>().values.insert(
+            self.id,
+            Box::new(SelectorValue {
+                value,
+                generation,
+                updated_at: this_run,
+            }),
+        );
+        generation
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> ReactiveSystem for Selector<T> {
+    type In = ();
+
+    type Out = T;
+
+    fn init(&mut self, world: &mut World) {
+        self.resource_id = Some(world.init_resource::<Selectors>());
+
+        let mut system = self.system.lock().unwrap_or_else(PoisonError::into_inner);
+        if !system.is_init {
+            system.system.init(world);
+            system.is_init = true;
+        }
+    }
+
+    fn is_changed(&mut self, world: DeferredWorld) -> bool {
+        Some(self.update(world)) != self.generation
+    }
+
+    fn is_changed_with_entity(&mut self, world: DeferredWorld, entity: Entity) -> bool {
+        Some(self.update(world)) != self.targets.get(&entity).copied()
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        let mut access = self
+            .system
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .system
+            .access();
+        if let Some(id) = self.resource_id {
+            let mut filtered = FilteredAccess::default();
+            filtered.add_write(id);
+            access.add(filtered);
+        }
+        access
+    }
+
+    fn run(&mut self, _input: Self::In, mut world: DeferredWorld, entity: Entity) -> Self::Out {
+        let generation = self.update(world.reborrow());
+        if self.generation != Some(generation) {
+            // Forget despawned targets once per output, so they don't pile up.
+            self.targets
+                .retain(|entity, _| world.get_entity(*entity).is_some());
+        }
+        self.generation = Some(generation);
+        self.targets.insert(entity, generation);
+        world
+            .resource::<Selectors>()
+            .get(self)
+            .expect("Selector has been updated")
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::ReactionTestApp, Reaction, Scope};
+    use std::sync::atomic::AtomicUsize;
+
+    #[derive(Resource)]
+    struct Value(i32);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Doubled(i32);
+
+    /// Returns a selector that doubles [`Value`], and the number of times it was computed.
+    fn doubled() -> (Selector<i32>, Arc<AtomicUsize>) {
+        let computes = Arc::new(AtomicUsize::new(0));
+        let counter = computes.clone();
+        let selector = Selector::new(move |_: In<Scope>, value: Res<Value>| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            value.0 * 2
+        });
+        (selector, computes)
+    }
+
+    fn insert_doubled(scope: In<Scope<i32>>, mut commands: Commands) {
+        commands.entity(scope.entity).insert(Doubled(scope.input));
+    }
+
+    #[test]
+    fn shared_selectors_compute_once() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().insert_resource(Value(1));
+        let (selector, computes) = doubled();
+        let a = app.spawn(Reaction::new(selector.clone().map(insert_doubled)));
+        let b = app.spawn(Reaction::new(selector.map(insert_doubled)));
+        app.step();
+        assert_eq!(computes.load(Ordering::Relaxed), 1);
+        assert_eq!(app.world().get::<Doubled>(a), Some(&Doubled(2)));
+        assert_eq!(app.world().get::<Doubled>(b), Some(&Doubled(2)));
+
+        app.step();
+        assert_eq!(computes.load(Ordering::Relaxed), 1);
+
+        app.world_mut().resource_mut::<Value>().0 = 2;
+        app.step();
+        assert_eq!(computes.load(Ordering::Relaxed), 2);
+        assert_eq!(app.world().get::<Doubled>(a), Some(&Doubled(4)));
+        assert_eq!(app.world().get::<Doubled>(b), Some(&Doubled(4)));
+    }
+
+    #[test]
+    fn every_target_sees_the_output() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().insert_resource(Value(1));
+        let a = app.spawn_target(());
+        let b = app.spawn_target(());
+        let (selector, computes) = doubled();
+        let mut reaction = Reaction::new(selector.map(insert_doubled));
+        reaction.add_target(a).add_target(b);
+        let reaction = app.spawn(reaction);
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+
+        app.world_mut().resource_mut::<Value>().0 = 2;
+        app.step();
+        assert_eq!(app.runs(reaction), 4);
+        assert_eq!(computes.load(Ordering::Relaxed), 2);
+        assert_eq!(app.world().get::<Doubled>(a), Some(&Doubled(4)));
+        assert_eq!(app.world().get::<Doubled>(b), Some(&Doubled(4)));
+    }
+}
@@ -1,5 +1,8 @@
+use crate::system_param::write_all_access;
 use bevy_ecs::{
+    component::ComponentId,
     entity::Entity,
+    query::FilteredAccessSet,
     world::{DeferredWorld, World},
 };
 
@@ -8,9 +11,59 @@ pub trait ReactiveSystem: Send + Sync {
 
     type Out;
 
+    /// Initialize this system for `world`.
+    ///
+    /// Initializing again resets all of its state, including [`Local`](bevy_ecs::system::Local)s,
+    /// so the system behaves as if it was just created.
     fn init(&mut self, world: &mut World);
 
     fn is_changed(&mut self, world: DeferredWorld) -> bool;
 
+    /// Returns `true` if this system has changed for the target `entity`.
+    ///
+    /// Defaults to [`ReactiveSystem::is_changed`].
+    fn is_changed_with_entity(&mut self, world: DeferredWorld, entity: Entity) -> bool {
+        let _ = entity;
+        self.is_changed(world)
+    }
+
+    /// Returns the world access of this system, once it has been initialized.
+    ///
+    /// Defaults to access of the entire world.
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        write_all_access()
+    }
+
     fn run(&mut self, input: Self::In, world: DeferredWorld, entity: Entity) -> Self::Out;
 }
+
+/// Boxed [`ReactiveSystem`], to store systems of different types together.
+///
+/// See [`box_reactive`](crate::box_reactive).
+pub type BoxedReactiveSystem<In = (), Out = ()> = Box<dyn ReactiveSystem<In = In, Out = Out>>;
+
+impl<S: ReactiveSystem + ?Sized> ReactiveSystem for Box<S> {
+    type In = S::In;
+
+    type Out = S::Out;
+
+    fn init(&mut self, world: &mut World) {
+        (**self).init(world);
+    }
+
+    fn is_changed(&mut self, world: DeferredWorld) -> bool {
+        (**self).is_changed(world)
+    }
+
+    fn is_changed_with_entity(&mut self, world: DeferredWorld, entity: Entity) -> bool {
+        (**self).is_changed_with_entity(world, entity)
+    }
+
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        (**self).access()
+    }
+
+    fn run(&mut self, input: Self::In, world: DeferredWorld, entity: Entity) -> Self::Out {
+        (**self).run(input, world, entity)
+    }
+}
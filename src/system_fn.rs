@@ -1,5 +1,8 @@
 use crate::{ReactiveSystem, ReactiveSystemParam, Scope};
-use bevy_ecs::{prelude::*, system::SystemParamItem, world::DeferredWorld};
+use bevy_ecs::{
+    component::ComponentId, prelude::*, query::FilteredAccessSet, system::SystemParamItem,
+    world::DeferredWorld,
+};
 use std::marker::PhantomData;
 
 pub trait ReactiveSystemParamFunction<Marker> {
@@ -41,6 +44,7 @@ where
 pub struct FunctionReactiveSystem<F, S, Marker> {
     pub(crate) f: F,
     pub(crate) state: Option<S>,
+    pub(crate) access: FilteredAccessSet<ComponentId>,
     pub(crate) _marker: PhantomData<Marker>,
 }
 
@@ -55,18 +59,33 @@ where
     type Out = F::Out;
 
     fn init(&mut self, world: &mut World) {
+        self.access = FilteredAccessSet::default();
+        F::Param::init_access(world, &mut self.access);
         self.state = Some(F::Param::init(world));
     }
 
+    fn access(&self) -> FilteredAccessSet<ComponentId> {
+        self.access.clone()
+    }
+
     fn is_changed(&mut self, world: DeferredWorld) -> bool {
         F::Param::is_changed(world, self.state.as_mut().unwrap())
     }
 
-    fn run(&mut self, input: Self::In, mut world: DeferredWorld, entity: Entity) -> Self::Out {
-        // TODO check for overlapping params
-        let mut world = world.reborrow();
-        let params = unsafe { F::Param::get(&mut world, self.state.as_mut().unwrap()) };
+    fn is_changed_with_entity(&mut self, world: DeferredWorld, entity: Entity) -> bool {
+        F::Param::is_changed_with_entity(world, self.state.as_mut().unwrap(), entity)
+    }
 
-        self.f.run(params, input, entity)
+    fn run(&mut self, input: Self::In, world: DeferredWorld, entity: Entity) -> Self::Out {
+        let out = {
+            // SAFETY: Like the `DeferredWorld` it comes from, the cell is only used to change
+            // component and resource data, and each param only accesses the data declared in
+            // `init_access`, which was checked for conflicts.
+            let world = world.as_unsafe_world_cell_readonly();
+            let params = unsafe { F::Param::get(world, self.state.as_mut().unwrap()) };
+            self.f.run(params, input, entity)
+        };
+        F::Param::after_run(world, self.state.as_mut().unwrap());
+        out
     }
 }
@@ -1,13 +1,25 @@
-use crate::ReactiveQueryData;
+use crate::{ReactiveQueryData, Scope};
 use bevy_ecs::{
-    component::Tick,
+    archetype::Archetype,
+    component::{ComponentId, Tick},
+    entity::{Entities, EntityHashSet},
     prelude::*,
-    query::{QueryData, QueryFilter, ReadOnlyQueryData, WorldQuery},
-    system::{SystemMeta, SystemParam, SystemState},
-    world::{unsafe_world_cell::UnsafeWorldCell, DeferredWorld},
+    query::{
+        FilteredAccess, FilteredAccessSet, QueryData, QueryEntityError, QueryFilter,
+        QuerySingleError, QueryState, ReadOnlyQueryData, WorldQuery,
+    },
+    system::{SystemMeta, SystemParam, SystemParamItem, SystemState},
+    world::{unsafe_world_cell::UnsafeWorldCell, CommandQueue, DeferredWorld},
+};
+use bevy_utils::{HashMap, Parallel};
+use std::{
+    any::{self, Any, TypeId},
+    error::Error,
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex, PoisonError},
 };
-use bevy_utils::HashSet;
-use std::error::Error;
 
 pub trait ReactiveSystemParam: SystemParam {
     type State: Send + Sync + 'static;
@@ -16,17 +28,194 @@ pub trait ReactiveSystemParam: SystemParam {
 
     fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) -> bool;
 
+    /// Returns `true` if this parameter has changed for the target `entity`.
+    ///
+    /// Parameters that don't track entities default to [`ReactiveSystemParam::is_changed`].
+    fn is_changed_with_entity(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveSystemParam>::State,
+        entity: Entity,
+    ) -> bool {
+        let _ = entity;
+        Self::is_changed(world, state)
+    }
+
+    /// Called after the reaction runs, to record the changes it made itself.
+    ///
+    /// Parameters that write to the world can use this so their own writes don't run
+    /// the reaction again. Defaults to doing nothing.
+    fn after_run(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) {
+        let _ = world;
+        let _ = state;
+    }
+
+    /// Register the world access of this parameter in `access`.
+    ///
+    /// # Panics
+    /// Panics if this parameter's access conflicts with an access already in `access`.
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        let _ = world;
+        let _ = access;
+    }
+
     /// Get the system parameter.
     ///
+    /// Writes to the world's command queue are buffered in the state,
+    /// and applied in [`ReactiveSystemParam::after_run`].
+    ///
     /// # Safety
-    /// `world` must not be mutated during this function call.
+    /// `world` must have access to the data declared in [`ReactiveSystemParam::init_access`],
+    /// and no other parameter may access that data in a conflicting way while the item is alive.
     unsafe fn get<'w: 's, 's>(
-        world: &'w mut DeferredWorld<'w>,
+        world: UnsafeWorldCell<'w>,
         state: &'s mut <Self as ReactiveSystemParam>::State,
     ) -> Self::Item<'w, 's>;
 }
 
 impl ReactiveSystemParam for Commands<'_, '_> {
+    type State = CommandQueue;
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        let _ = world;
+
+        CommandQueue::default()
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) -> bool {
+        let _ = world;
+        let _ = state;
+
+        false
+    }
+
+    fn after_run(mut world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) {
+        world.commands().append(state);
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        add_deferred_access(world, access);
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        Commands::new_from_entities(state, world.entities())
+    }
+}
+
+/// Reactive system parameter to queue commands from parallel iteration,
+/// like [`ParallelCommands`](bevy_ecs::system::ParallelCommands).
+///
+/// Each thread queues commands into its own buffer.
+/// When the reaction returns, the buffers are appended to the world's command queue
+/// along with the commands of the reaction's other parameters, in the order they're declared,
+/// so these commands are applied after any [`Commands`] declared before this parameter.
+/// Commands from different threads are applied in an unspecified order.
+pub struct ReactiveParallelCommands<'w, 's> {
+    commands: Commands<'w, 's>,
+    entities: &'w Entities,
+    queues: &'s mut Parallel<CommandQueue>,
+}
+
+impl ReactiveParallelCommands<'_, '_> {
+    /// Temporarily provides access to the [`Commands`] for the current thread.
+    pub fn command_scope<R>(&self, f: impl FnOnce(Commands) -> R) -> R {
+        self.queues
+            .scope(|queue| f(Commands::new_from_entities(queue, self.entities)))
+    }
+}
+
+impl Drop for ReactiveParallelCommands<'_, '_> {
+    fn drop(&mut self) {
+        for queue in self.queues.iter_mut() {
+            self.commands.append(queue);
+        }
+    }
+}
+
+/// Outside of reactions, [`ReactiveParallelCommands`] works like
+/// [`ParallelCommands`](bevy_ecs::system::ParallelCommands).
+unsafe impl SystemParam for ReactiveParallelCommands<'_, '_> {
+    type State = (
+        <Commands<'static, 'static> as SystemParam>::State,
+        Parallel<CommandQueue>,
+    );
+
+    type Item<'world, 'state> = ReactiveParallelCommands<'world, 'state>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        (
+            Commands::init_state(world, system_meta),
+            Parallel::default(),
+        )
+    }
+
+    fn apply(state: &mut Self::State, system_meta: &SystemMeta, world: &mut World) {
+        Commands::apply(&mut state.0, system_meta, world);
+    }
+
+    fn queue(state: &mut Self::State, system_meta: &SystemMeta, world: DeferredWorld) {
+        Commands::queue(&mut state.0, system_meta, world);
+    }
+
+    unsafe fn get_param<'world, 'state>(
+        state: &'state mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'world>,
+        change_tick: Tick,
+    ) -> Self::Item<'world, 'state> {
+        let (commands, queues) = state;
+
+        ReactiveParallelCommands {
+            // SAFETY: Commands only read the entities of `world`.
+            commands: unsafe { Commands::get_param(commands, system_meta, world, change_tick) },
+            entities: world.entities(),
+            queues,
+        }
+    }
+}
+
+impl ReactiveSystemParam for ReactiveParallelCommands<'_, '_> {
+    type State = (CommandQueue, Parallel<CommandQueue>);
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        let _ = world;
+
+        (CommandQueue::default(), Parallel::default())
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) -> bool {
+        let _ = world;
+        let _ = state;
+
+        false
+    }
+
+    fn after_run(mut world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) {
+        world.commands().append(&mut state.0);
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        add_deferred_access(world, access);
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        let (queue, queues) = state;
+
+        ReactiveParallelCommands {
+            commands: Commands::new_from_entities(queue, world.entities()),
+            entities: world.entities(),
+            queues,
+        }
+    }
+}
+
+/// Marker parameter that never changes, for generic or derived parameters.
+impl<T: ?Sized> ReactiveSystemParam for PhantomData<T> {
     type State = ();
 
     fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
@@ -41,12 +230,13 @@ impl ReactiveSystemParam for Commands<'_, '_> {
     }
 
     unsafe fn get<'w: 's, 's>(
-        world: &'w mut DeferredWorld<'w>,
+        world: UnsafeWorldCell<'w>,
         state: &'s mut <Self as ReactiveSystemParam>::State,
     ) -> Self::Item<'w, 's> {
+        let _ = world;
         let _ = state;
 
-        world.commands()
+        PhantomData
     }
 }
 
@@ -65,10 +255,11 @@ impl<T: FromWorld + Send> ReactiveSystemParam for Local<'_, T> {
     }
 
     unsafe fn get<'w: 's, 's>(
-        world: &'w mut DeferredWorld<'w>,
+        world: UnsafeWorldCell<'w>,
         state: &'s mut <Self as ReactiveSystemParam>::State,
     ) -> Self::Item<'w, 's> {
-        state.get(world)
+        // SAFETY: Locals don't access the world.
+        unsafe { get_state(state, world) }
     }
 }
 
@@ -84,12 +275,29 @@ impl<R: Resource> ReactiveSystemParam for Res<'_, R> {
         world.resource_ref::<R>().is_changed()
     }
 
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        // Make sure the resource has a `ComponentId`, even if it isn't inserted yet.
+        SystemState::<Res<R>>::new(world);
+        let id = world.components().resource_id::<R>().unwrap();
+
+        let mut filtered = FilteredAccess::default();
+        filtered.add_read(id);
+        add_access::<Self>(world, access, filtered);
+    }
+
     unsafe fn get<'w: 's, 's>(
-        world: &'w mut DeferredWorld<'w>,
+        world: UnsafeWorldCell<'w>,
         state: &'s mut <Self as ReactiveSystemParam>::State,
     ) -> Self::Item<'w, 's> {
         let _ = state;
-        world.resource_ref::<R>()
+
+        // SAFETY: The resource is only read, as declared in `init_access`.
+        unsafe { world.get_resource_ref::<R>() }.unwrap_or_else(|| {
+            panic!(
+                "Resource requested by a reaction does not exist: {}",
+                any::type_name::<R>()
+            )
+        })
     }
 }
 
@@ -111,11 +319,25 @@ where
         <D as ReactiveQueryData<F>>::is_changed(world, state)
     }
 
+    fn is_changed_with_entity(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveSystemParam>::State,
+        entity: Entity,
+    ) -> bool {
+        <D as ReactiveQueryData<F>>::is_changed_with_entity(world, state, entity)
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        let query = QueryState::<D, F>::new(world);
+        add_access::<Self>(world, access, query.component_access().clone());
+    }
+
     unsafe fn get<'w: 's, 's>(
-        world: &'w mut DeferredWorld<'w>,
+        world: UnsafeWorldCell<'w>,
         state: &'s mut <Self as ReactiveSystemParam>::State,
     ) -> Self::Item<'w, 's> {
-        <D as ReactiveQueryData<F>>::get(world, state)
+        // SAFETY: The caller ensures `world` has access to the query's data.
+        unsafe { <D as ReactiveQueryData<F>>::get(world, state) }
     }
 }
 
@@ -133,11 +355,28 @@ impl<T: ReactiveSystemParam> ReactiveSystemParam for (T,) {
         T::is_changed(world, state)
     }
 
+    fn is_changed_with_entity(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveSystemParam>::State,
+        entity: Entity,
+    ) -> bool {
+        T::is_changed_with_entity(world, state, entity)
+    }
+
+    fn after_run(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) {
+        T::after_run(world, state);
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        T::init_access(world, access)
+    }
+
     unsafe fn get<'w: 's, 's>(
-        world: &'w mut DeferredWorld<'w>,
+        world: UnsafeWorldCell<'w>,
         state: &'s mut <Self as ReactiveSystemParam>::State,
     ) -> Self::Item<'w, 's> {
-        (T::get(world, state),)
+        // SAFETY: The caller ensures `world` has access to the data of `T`.
+        (unsafe { T::get(world, state) },)
     }
 }
 
@@ -155,53 +394,101 @@ impl<T1: ReactiveSystemParam, T2: ReactiveSystemParam> ReactiveSystemParam for (
         mut world: DeferredWorld,
         state: &mut <Self as ReactiveSystemParam>::State,
     ) -> bool {
-        T1::is_changed(world.reborrow(), &mut state.0) || T2::is_changed(world, &mut state.1)
+        // Poll both params so neither falls behind, like pruning tracked entities in `ReactiveQuery`.
+        T1::is_changed(world.reborrow(), &mut state.0) | T2::is_changed(world, &mut state.1)
+    }
+
+    fn is_changed_with_entity(
+        mut world: DeferredWorld,
+        state: &mut <Self as ReactiveSystemParam>::State,
+        entity: Entity,
+    ) -> bool {
+        T1::is_changed_with_entity(world.reborrow(), &mut state.0, entity)
+            | T2::is_changed_with_entity(world, &mut state.1, entity)
+    }
+
+    fn after_run(mut world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) {
+        T1::after_run(world.reborrow(), &mut state.0);
+        T2::after_run(world, &mut state.1);
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        T1::init_access(world, access);
+        T2::init_access(world, access);
     }
 
     unsafe fn get<'w: 's, 's>(
-        world: &'w mut DeferredWorld<'w>,
+        world: UnsafeWorldCell<'w>,
         state: &'s mut <Self as ReactiveSystemParam>::State,
     ) -> Self::Item<'w, 's> {
-        let world_ptr = world as *mut _;
-        (
-            T1::get(unsafe { &mut *world_ptr }, &mut state.0),
-            T2::get(unsafe { &mut *world_ptr }, &mut state.1),
-        )
+        // SAFETY: Access is checked for conflicts in `init_access`,
+        // so both params can get their items from the same `world`.
+        (unsafe { T1::get(world, &mut state.0) }, unsafe {
+            T2::get(world, &mut state.1)
+        })
     }
 }
 
-pub struct ReactiveQueryState<D: QueryData + 'static, F: QueryFilter + 'static, S> {
-    query: SystemState<Query<'static, 'static, D, F>>,
-    query_state: S,
-    entities: HashSet<Entity>,
+/// Initialize the state of the reactive parameter `P`, to poll it with [`changed`].
+pub fn init_state<P: ReactiveSystemParam>(world: &mut World) -> <P as ReactiveSystemParam>::State {
+    P::init(world)
 }
 
-pub struct ReactiveQuery<'w, 's, D: ReadOnlyQueryData + 'static, F: QueryFilter + 'static = ()> {
-    query: Query<'w, 's, D, F>,
-    entities: &'s mut HashSet<Entity>,
+/// Returns `true` if the reactive parameter `P` has changed since it was last polled.
+///
+/// This can be used to build custom drivers without a [`Reaction`](crate::Reaction).
+pub fn changed<P: ReactiveSystemParam>(
+    world: DeferredWorld,
+    state: &mut <P as ReactiveSystemParam>::State,
+) -> bool {
+    P::is_changed(world, state)
 }
 
-impl<'w, 's, D: ReadOnlyQueryData + 'static, F: QueryFilter + 'static> ReactiveQuery<'w, 's, D, F> {
-    pub fn get(&mut self, entity: Entity) -> Result<<D as WorldQuery>::Item<'_>, Box<dyn Error>> {
-        self.entities.insert(entity);
+/// Reactive system parameter that reads `P` without reacting to its changes.
+///
+/// This is useful for values like debug toggles that a reaction reads,
+/// but that shouldn't cause the reaction to run on their own.
+pub struct Peek<P>(pub P);
+
+impl<P> Deref for Peek<P> {
+    type Target = P;
 
-        self.query
-            .get(entity)
-            .map_err(|e| Box::new(e) as Box<dyn Error>)
+    fn deref(&self) -> &Self::Target {
+        &self.0
     }
 }
 
-unsafe impl<D: ReadOnlyQueryData + 'static, F: QueryFilter + 'static> SystemParam
-    for ReactiveQuery<'_, '_, D, F>
-{
-    type State = ();
+impl<P> DerefMut for Peek<P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
 
-    type Item<'world, 'state> = ReactiveQuery<'world, 'state, D, F>;
+/// Outside of reactions, [`Peek`] is the same as `P`.
+unsafe impl<P: SystemParam> SystemParam for Peek<P> {
+    type State = P::State;
+
+    type Item<'world, 'state> = Peek<P::Item<'world, 'state>>;
 
     fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
-        let _ = world;
-        let _ = system_meta;
-        todo!()
+        P::init_state(world, system_meta)
+    }
+
+    unsafe fn new_archetype(
+        state: &mut Self::State,
+        archetype: &Archetype,
+        system_meta: &mut SystemMeta,
+    ) {
+        // SAFETY: The caller ensures `archetype` is from the world used to initialize `state`.
+        unsafe { P::new_archetype(state, archetype, system_meta) }
+    }
+
+    fn apply(state: &mut Self::State, system_meta: &SystemMeta, world: &mut World) {
+        P::apply(state, system_meta, world);
+    }
+
+    fn queue(state: &mut Self::State, system_meta: &SystemMeta, world: DeferredWorld) {
+        P::queue(state, system_meta, world);
     }
 
     unsafe fn get_param<'world, 'state>(
@@ -210,53 +497,1413 @@ unsafe impl<D: ReadOnlyQueryData + 'static, F: QueryFilter + 'static> SystemPara
         world: UnsafeWorldCell<'world>,
         change_tick: Tick,
     ) -> Self::Item<'world, 'state> {
-        let _ = state;
-        let _ = system_meta;
-        let _ = world;
-        let _ = change_tick;
-        todo!()
+        // SAFETY: The caller ensures `world` has access to the data of `P`.
+        Peek(unsafe { P::get_param(state, system_meta, world, change_tick) })
     }
 }
 
-impl<D, F> ReactiveSystemParam for ReactiveQuery<'_, '_, D, F>
-where
-    D: ReactiveQueryData<F> + ReadOnlyQueryData + 'static,
-    F: QueryFilter + 'static,
-{
-    type State = ReactiveQueryState<D, F, <D as ReactiveQueryData<F>>::State>;
+impl<P: ReactiveSystemParam> ReactiveSystemParam for Peek<P> {
+    type State = <P as ReactiveSystemParam>::State;
 
     fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
-        ReactiveQueryState {
-            query: SystemState::new(world),
-            query_state: D::init(world),
-            entities: HashSet::new(),
-        }
+        P::init(world)
     }
 
-    fn is_changed(
-        mut world: DeferredWorld,
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) -> bool {
+        let _ = world;
+        let _ = state;
+
+        false
+    }
+
+    fn is_changed_with_entity(
+        world: DeferredWorld,
         state: &mut <Self as ReactiveSystemParam>::State,
+        entity: Entity,
     ) -> bool {
-        if state.entities.is_empty() {
-            return true;
-        }
-
-        for entity in state.entities.iter() {
-            if D::is_changed_with_entity(world.reborrow(), &mut state.query_state, *entity) {
-                return true;
-            }
-        }
+        let _ = world;
+        let _ = state;
+        let _ = entity;
 
         false
     }
 
+    fn after_run(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) {
+        P::after_run(world, state);
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        P::init_access(world, access)
+    }
+
     unsafe fn get<'w: 's, 's>(
-        world: &'w mut DeferredWorld<'w>,
+        world: UnsafeWorldCell<'w>,
         state: &'s mut <Self as ReactiveSystemParam>::State,
     ) -> Self::Item<'w, 's> {
-        ReactiveQuery {
-            query: state.query.get(world),
-            entities: &mut state.entities,
-        }
+        // SAFETY: The caller ensures `world` has access to the data of `P`.
+        Peek(unsafe { P::get(world, state) })
+    }
+}
+
+/// Reactive system parameter that reacts to changes of `P`, except when it was just added.
+///
+/// Resources are changed when they're inserted, so a reaction with `Mutated<Res<R>>`
+/// won't run on startup, but only once `R` is mutated.
+pub struct Mutated<P>(pub P);
+
+impl<P> Deref for Mutated<P> {
+    type Target = P;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<P> DerefMut for Mutated<P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Outside of reactions, [`Mutated`] is the same as `P`.
+unsafe impl<P: SystemParam> SystemParam for Mutated<P> {
+    type State = P::State;
+
+    type Item<'world, 'state> = Mutated<P::Item<'world, 'state>>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        P::init_state(world, system_meta)
+    }
+
+    unsafe fn new_archetype(
+        state: &mut Self::State,
+        archetype: &Archetype,
+        system_meta: &mut SystemMeta,
+    ) {
+        // SAFETY: The caller ensures `archetype` is from the world used to initialize `state`.
+        unsafe { P::new_archetype(state, archetype, system_meta) }
+    }
+
+    fn apply(state: &mut Self::State, system_meta: &SystemMeta, world: &mut World) {
+        P::apply(state, system_meta, world);
+    }
+
+    fn queue(state: &mut Self::State, system_meta: &SystemMeta, world: DeferredWorld) {
+        P::queue(state, system_meta, world);
+    }
+
+    unsafe fn get_param<'world, 'state>(
+        state: &'state mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'world>,
+        change_tick: Tick,
+    ) -> Self::Item<'world, 'state> {
+        // SAFETY: The caller ensures `world` has access to the data of `P`.
+        Mutated(unsafe { P::get_param(state, system_meta, world, change_tick) })
+    }
+}
+
+impl<R: Resource> ReactiveSystemParam for Mutated<Res<'_, R>> {
+    type State = <Res<'static, R> as ReactiveSystemParam>::State;
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        Res::<R>::init(world)
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) -> bool {
+        let _ = state;
+        let resource = world.resource_ref::<R>();
+        resource.is_changed() && !resource.is_added()
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        Res::<R>::init_access(world, access)
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        // SAFETY: The caller ensures `world` has access to the resource.
+        Mutated(unsafe { Res::<R>::get(world, state) })
+    }
+}
+
+/// Reactive system parameter for read-only access to the entire [`World`].
+///
+/// This is always changed, so it disables fine-grained change detection
+/// and a reaction with it runs every time it's polled.
+/// Use [`Peek<ReactiveWorld>`](Peek) to read the world only when other parameters change.
+pub struct ReactiveWorld<'w>(&'w World);
+
+impl Deref for ReactiveWorld<'_> {
+    type Target = World;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+/// Outside of reactions, [`ReactiveWorld`] is the same as `&World`.
+unsafe impl SystemParam for ReactiveWorld<'_> {
+    type State = ();
+
+    type Item<'world, 'state> = ReactiveWorld<'world>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        <&World>::init_state(world, system_meta)
+    }
+
+    unsafe fn get_param<'world, 'state>(
+        state: &'state mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'world>,
+        change_tick: Tick,
+    ) -> Self::Item<'world, 'state> {
+        // SAFETY: The caller ensures `world` has read access to the entire world.
+        ReactiveWorld(unsafe { <&World>::get_param(state, system_meta, world, change_tick) })
+    }
+}
+
+impl ReactiveSystemParam for ReactiveWorld<'_> {
+    type State = ();
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        let _ = world;
+    }
+
+    fn is_changed(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) -> bool {
+        let _ = world;
+        let _ = state;
+
+        true
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        let _ = world;
+
+        // Reactive parameters only write to the command queue, which isn't checked for conflicts.
+        let mut filtered = FilteredAccess::default();
+        filtered.read_all();
+        access.add(filtered);
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        let _ = state;
+
+        // SAFETY: The whole world is only read, as declared in `init_access`.
+        ReactiveWorld(unsafe { world.world() })
+    }
+}
+
+/// Get the parameter `P` of `state` from `world`, like [`SystemState::get`].
+///
+/// # Safety
+/// `world` must have access to the data of `P`,
+/// and no other parameter may access that data in a conflicting way while the item is alive.
+pub(crate) unsafe fn get_state<'w, 's, P: SystemParam>(
+    state: &'s mut SystemState<P>,
+    world: UnsafeWorldCell<'w>,
+) -> SystemParamItem<'w, 's, P> {
+    state.update_archetypes_unsafe_world_cell(world);
+
+    // SAFETY: The caller ensures `world` has access to the data of `P`.
+    unsafe { state.get_unchecked_manual(world) }
+}
+
+/// Add `filtered` to `access`, panicking if it conflicts with a previous parameter.
+pub(crate) fn add_access<P>(
+    world: &World,
+    access: &mut FilteredAccessSet<ComponentId>,
+    filtered: FilteredAccess<ComponentId>,
+) {
+    let conflicts = access.get_conflicts_single(&filtered);
+    if !conflicts.is_empty() {
+        let names = conflicts
+            .iter()
+            .map(|id| world.components().get_name(*id).unwrap_or("<unknown>"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        panic!(
+            "{} in reaction conflicts with a previous parameter's access to [{}]. Consider removing the duplicate access.",
+            any::type_name::<P>(),
+            names
+        );
+    }
+
+    access.add(filtered);
+}
+
+/// Marker for access to the world's command queue.
+#[derive(Component)]
+struct DeferredAccess;
+
+/// Add write access to the world's command queue to `access`.
+///
+/// Reactions share a single command queue, so no two reactions using commands can run in parallel.
+/// This isn't checked for conflicts, as a single reaction can use commands more than once.
+/// Returns access to the entire world, which conflicts with any other access.
+///
+/// [`FilteredAccessSet::write_all`] alone only marks the combined access,
+/// which is still compatible with a set without any filtered access.
+pub(crate) fn write_all_access() -> FilteredAccessSet<ComponentId> {
+    let mut filtered = FilteredAccess::default();
+    filtered.write_all();
+
+    let mut access = FilteredAccessSet::default();
+    access.write_all();
+    access.add(filtered);
+    access
+}
+
+pub(crate) fn add_deferred_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+    let id = world.init_component::<DeferredAccess>();
+
+    let mut filtered = FilteredAccess::default();
+    filtered.add_write(id);
+    access.add(filtered);
+}
+
+/// Memory policy for the entities tracked by each [`ReactiveQuery`].
+///
+/// Add this resource with [`ReactionPlugin::tracking_policy`](crate::ReactionPlugin::tracking_policy)
+/// before reactions are spawned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Resource)]
+pub struct TrackingPolicy {
+    /// Initial capacity of each tracked set.
+    pub initial_capacity: usize,
+
+    /// Shrink a tracked set once its capacity is more than this many times its length.
+    /// Sets are never shrunk below `initial_capacity`.
+    pub shrink_ratio: usize,
+}
+
+impl Default for TrackingPolicy {
+    fn default() -> Self {
+        Self {
+            initial_capacity: 0,
+            shrink_ratio: 4,
+        }
+    }
+}
+
+/// Entities matching the queries of [`ReactiveQuery`] parameters, shared by query shape.
+///
+/// Reactions with the same `D` and `F` share a single [`QueryState`] to find matching entities,
+/// which is only updated once per change tick, instead of once per reaction.
+#[derive(Default, Resource)]
+pub struct ReactiveQueryCache {
+    matches: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl ReactiveQueryCache {
+    /// Returns the number of query shapes in the cache.
+    pub fn len(&self) -> usize {
+        self.matches.len()
+    }
+
+    /// Returns `true` if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty()
+    }
+
+    /// Get the shared matches for `D` and `F`, creating them if they don't exist.
+    fn get_or_init<D, F>(world: &mut World) -> Arc<Mutex<SharedMatches<D, F>>>
+    where
+        D: ReadOnlyQueryData + 'static,
+        F: QueryFilter + 'static,
+    {
+        let id = TypeId::of::<QueryState<(Entity, D), F>>();
+        if let Some(shared) = world
+            .get_resource::<Self>()
+            .and_then(|cache| cache.matches.get(&id))
+            .and_then(|shared| shared.downcast_ref::<Arc<Mutex<SharedMatches<D, F>>>>())
+        {
+            return shared.clone();
+        }
+
+        let shared = Arc::new(Mutex::new(SharedMatches {
+            matches: QueryState::new(world),
+            matched: EntityHashSet::default(),
+            generation: 0,
+            updated_at: None,
+        }));
+        world
+            .get_resource_or_insert_with(Self::default)
+            .matches
+            .insert(id, Box::new(shared.clone()));
+        shared
+    }
+}
+
+struct SharedMatches<D: QueryData + 'static, F: QueryFilter + 'static> {
+    matches: QueryState<(Entity, D), F>,
+    matched: EntityHashSet,
+    /// Incremented each time `matched` changes.
+    generation: u32,
+    updated_at: Option<(Tick, u32)>,
+}
+
+impl<D: ReadOnlyQueryData + 'static, F: QueryFilter + 'static> SharedMatches<D, F> {
+    fn update(&mut self, world: &World) {
+        // Entities can only be spawned or moved between ticks, unless they're spawned directly.
+        let updated_at = (world.last_change_tick(), world.entities().len());
+        if self.updated_at == Some(updated_at) {
+            return;
+        }
+        self.updated_at = Some(updated_at);
+
+        self.matches.update_archetypes(world);
+
+        let mut count = 0;
+        let mut is_changed = false;
+        for (entity, _) in self.matches.iter_manual(world) {
+            count += 1;
+            if !self.matched.contains(&entity) {
+                is_changed = true;
+                break;
+            }
+        }
+        is_changed |= count != self.matched.len();
+
+        if is_changed {
+            self.matched = self
+                .matches
+                .iter_manual(world)
+                .map(|(entity, _)| entity)
+                .collect();
+            self.generation = self.generation.wrapping_add(1);
+        }
+    }
+}
+
+pub struct ReactiveQueryState<D: QueryData + 'static, F: QueryFilter + 'static, S> {
+    query: SystemState<Query<'static, 'static, (Entity, D), F>>,
+    query_state: S,
+    entities: EntityHashSet,
+    shared: Arc<Mutex<SharedMatches<D, F>>>,
+    generation: u32,
+    policy: TrackingPolicy,
+    /// Most entities tracked at once since the set was last shrunk.
+    peak: usize,
+}
+
+impl<D: ReadOnlyQueryData + 'static, F: QueryFilter + 'static, S> ReactiveQueryState<D, F, S> {
+    /// Returns `true` if any entities started or stopped matching the query since the last check.
+    ///
+    /// This also prunes tracked entities that no longer match.
+    fn update_matched(&mut self, world: &World) -> bool {
+        let mut shared = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+        shared.update(world);
+
+        let is_changed = shared.generation != self.generation;
+        self.generation = shared.generation;
+
+        // Stop tracking entities that were despawned or don't match the query,
+        // so the tracked set doesn't grow forever. They'll change `matched` if they start matching.
+        self.peak = self.peak.max(self.entities.len());
+        let matched = &shared.matched;
+        self.entities.retain(|entity| matched.contains(entity));
+        drop(shared);
+
+        // Removed entries lower the reported capacity without freeing memory,
+        // so compare against the peak length instead.
+        let TrackingPolicy {
+            initial_capacity,
+            shrink_ratio,
+        } = self.policy;
+        let len = self.entities.len();
+        if self.peak > initial_capacity.max(len.saturating_mul(shrink_ratio)) {
+            self.entities.shrink_to(initial_capacity.max(len));
+            self.peak = len;
+        }
+
+        is_changed
+    }
+
+    /// Only keep the tracked entities where `f` returns `true`.
+    pub(crate) fn retain_tracked(&mut self, f: impl FnMut(&Entity) -> bool) {
+        self.entities.retain(f);
+    }
+}
+
+impl<D, F> ReactiveQueryState<D, F, <D as ReactiveQueryData<F>>::State>
+where
+    D: ReactiveQueryData<F> + ReadOnlyQueryData + 'static,
+    F: QueryFilter + 'static,
+{
+    /// Returns `true` if any tracked entity changed since the last poll,
+    /// like [`changed`] for a [`ReactiveQuery`].
+    pub fn is_changed(&mut self, world: DeferredWorld) -> bool {
+        ReactiveQuery::<D, F>::is_changed(world, self)
+    }
+
+    /// Get the [`ReactiveQuery`] for this state.
+    pub fn query<'w>(&'w mut self, world: &'w World) -> ReactiveQuery<'w, 'w, D, F> {
+        // SAFETY: The query is read-only, and `world` is borrowed for as long as it's alive.
+        unsafe {
+            <ReactiveQuery<D, F> as ReactiveSystemParam>::get(
+                world.as_unsafe_world_cell_readonly(),
+                self,
+            )
+        }
+    }
+}
+
+/// An error that occurs when getting an entity from a [`ReactiveQuery`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReactiveQueryError {
+    /// The components of the entity don't match the query.
+    QueryDoesNotMatch(Entity),
+    /// The entity doesn't exist.
+    NoSuchEntity(Entity),
+    /// The entity was requested mutably more than once.
+    AliasedMutability(Entity),
+}
+
+impl From<QueryEntityError> for ReactiveQueryError {
+    fn from(error: QueryEntityError) -> Self {
+        match error {
+            QueryEntityError::QueryDoesNotMatch(entity) => Self::QueryDoesNotMatch(entity),
+            QueryEntityError::NoSuchEntity(entity) => Self::NoSuchEntity(entity),
+            QueryEntityError::AliasedMutability(entity) => Self::AliasedMutability(entity),
+        }
+    }
+}
+
+impl fmt::Display for ReactiveQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::QueryDoesNotMatch(entity) => {
+                write!(
+                    f,
+                    "The components of entity {entity:?} do not match the query"
+                )
+            }
+            Self::NoSuchEntity(entity) => write!(f, "The entity {entity:?} does not exist"),
+            Self::AliasedMutability(entity) => {
+                write!(
+                    f,
+                    "The entity {entity:?} was requested mutably more than once"
+                )
+            }
+        }
+    }
+}
+
+impl Error for ReactiveQueryError {}
+
+pub struct ReactiveQuery<'w, 's, D: ReadOnlyQueryData + 'static, F: QueryFilter + 'static = ()> {
+    query: Query<'w, 's, (Entity, D), F>,
+    entities: &'s mut EntityHashSet,
+}
+
+impl<D: ReadOnlyQueryData + 'static> ReactiveQuery<'_, '_, D> {
+    /// Initialize the state of a `ReactiveQuery<D, F>` for custom drivers,
+    /// choosing the filter `F` at the call site,
+    /// like `ReactiveQuery::<&Health>::init_filtered::<With<Player>>(world)`.
+    ///
+    /// Entities that stop matching `F` stop being tracked, and count as a change.
+    pub fn init_filtered<F>(
+        world: &mut World,
+    ) -> ReactiveQueryState<D, F, <D as ReactiveQueryData<F>>::State>
+    where
+        D: ReactiveQueryData<F>,
+        F: QueryFilter + 'static,
+    {
+        init_state::<ReactiveQuery<D, F>>(world)
+    }
+}
+
+impl<'w, 's, D: ReadOnlyQueryData + 'static, F: QueryFilter + 'static> ReactiveQuery<'w, 's, D, F> {
+    /// Get the item for `entity`, tracking it for changes.
+    ///
+    /// Tracking is idempotent, so getting the same entity more than once in a run
+    /// tracks it once and returns the same item each time.
+    pub fn get(
+        &mut self,
+        entity: Entity,
+    ) -> Result<<D as WorldQuery>::Item<'_>, ReactiveQueryError> {
+        self.entities.insert(entity);
+
+        let (_, item) = self.query.get(entity)?;
+        Ok(item)
+    }
+
+    /// Get the item for the entity of `scope`, tracking it for changes.
+    ///
+    /// This is the reaction's own entity, unless the reaction has targets.
+    pub fn get_scope<T>(
+        &mut self,
+        scope: &Scope<T>,
+    ) -> Result<<D as WorldQuery>::Item<'_>, ReactiveQueryError> {
+        self.get(scope.entity)
+    }
+
+    /// Get the items for `N` entities at once, tracking each of them.
+    ///
+    /// Like [`Query::get_many`], the same entity can be requested more than once.
+    /// If any entity fails, its error is returned and,
+    /// like [`ReactiveQuery::get_single`], no entities are tracked.
+    pub fn get_many<const N: usize>(
+        &mut self,
+        entities: [Entity; N],
+    ) -> Result<[<D as WorldQuery>::Item<'_>; N], ReactiveQueryError> {
+        let items = self.query.get_many(entities)?;
+        self.entities.extend(entities);
+        Ok(items.map(|(_, item)| item))
+    }
+
+    /// Get the single matching item, tracking its entity.
+    ///
+    /// # Panics
+    /// Panics if the number of matching entities isn't exactly one.
+    pub fn single(&mut self) -> <D as WorldQuery>::Item<'_> {
+        self.get_single().unwrap()
+    }
+
+    /// Get the single matching item, tracking its entity.
+    ///
+    /// Returns a [`QuerySingleError`] if there are no or multiple matching entities.
+    pub fn get_single(&mut self) -> Result<<D as WorldQuery>::Item<'_>, QuerySingleError> {
+        let (entity, item) = self.query.get_single()?;
+        self.entities.insert(entity);
+        Ok(item)
+    }
+
+    /// Iterate over the entities tracked by this query.
+    pub fn tracked(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.iter().copied()
+    }
+
+    /// Returns the capacity of the set of tracked entities.
+    ///
+    /// See [`TrackingPolicy`] to control how it shrinks.
+    pub fn tracked_capacity(&self) -> usize {
+        self.entities.capacity()
+    }
+
+    /// Run `f` for each matching entity and its item, tracking each of them.
+    pub fn for_each(&mut self, mut f: impl FnMut(Entity, <D as WorldQuery>::Item<'_>)) {
+        for (entity, item) in self.query.iter() {
+            self.entities.insert(entity);
+            f(entity, item);
+        }
+    }
+
+    /// Returns `true` if `entity` is tracked by this query.
+    ///
+    /// Unlike [`ReactiveQuery::get`], this doesn't start tracking `entity`.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.contains(&entity)
+    }
+
+    /// Iterate over all matching items, tracking each entity that is visited.
+    pub fn iter(&mut self) -> impl Iterator<Item = <D as WorldQuery>::Item<'_>> {
+        let entities = &mut *self.entities;
+
+        self.query.iter().map(move |(entity, item)| {
+            entities.insert(entity);
+            item
+        })
+    }
+}
+
+/// Outside of reactions, a [`ReactiveQuery`] works like a [`Query`],
+/// and the entities it tracks are kept with the system.
+unsafe impl<D: ReadOnlyQueryData + 'static, F: QueryFilter + 'static> SystemParam
+    for ReactiveQuery<'_, '_, D, F>
+{
+    type State = (QueryState<(Entity, D), F>, EntityHashSet);
+
+    type Item<'world, 'state> = ReactiveQuery<'world, 'state, D, F>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        (
+            Query::<(Entity, D), F>::init_state(world, system_meta),
+            EntityHashSet::default(),
+        )
+    }
+
+    unsafe fn new_archetype(
+        state: &mut Self::State,
+        archetype: &Archetype,
+        system_meta: &mut SystemMeta,
+    ) {
+        // SAFETY: The caller ensures `archetype` is from the world used to initialize `state`.
+        unsafe { Query::<(Entity, D), F>::new_archetype(&mut state.0, archetype, system_meta) }
+    }
+
+    unsafe fn get_param<'world, 'state>(
+        state: &'state mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'world>,
+        change_tick: Tick,
+    ) -> Self::Item<'world, 'state> {
+        let (query, entities) = state;
+
+        ReactiveQuery {
+            // SAFETY: The caller ensures `world` has access to the query's data.
+            query: unsafe { Query::get_param(query, system_meta, world, change_tick) },
+            entities,
+        }
+    }
+}
+
+impl<D, F> ReactiveSystemParam for ReactiveQuery<'_, '_, D, F>
+where
+    D: ReactiveQueryData<F> + ReadOnlyQueryData + 'static,
+    F: QueryFilter + 'static,
+{
+    type State = ReactiveQueryState<D, F, <D as ReactiveQueryData<F>>::State>;
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        let policy = world
+            .get_resource::<TrackingPolicy>()
+            .copied()
+            .unwrap_or_default();
+        let shared = ReactiveQueryCache::get_or_init::<D, F>(world);
+        let generation = shared
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .generation;
+        ReactiveQueryState {
+            query: SystemState::new(world),
+            query_state: D::init(world),
+            entities: EntityHashSet::with_capacity_and_hasher(
+                policy.initial_capacity,
+                Default::default(),
+            ),
+            shared,
+            generation,
+            policy,
+            peak: 0,
+        }
+    }
+
+    fn is_changed(
+        mut world: DeferredWorld,
+        state: &mut <Self as ReactiveSystemParam>::State,
+    ) -> bool {
+        // Entities that newly match (or stop matching) the query aren't tracked yet.
+        if state.update_matched(&world) {
+            return true;
+        }
+
+        if state.entities.is_empty() {
+            return true;
+        }
+
+        for entity in state.entities.iter() {
+            if D::is_changed_with_entity(world.reborrow(), &mut state.query_state, *entity) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn is_changed_with_entity(
+        world: DeferredWorld,
+        state: &mut <Self as ReactiveSystemParam>::State,
+        entity: Entity,
+    ) -> bool {
+        // A target that this query doesn't read can depend on any tracked entity.
+        if !state.entities.contains(&entity) {
+            return Self::is_changed(world, state);
+        }
+
+        // Only check the target itself, so changes to other targets don't run it.
+        D::is_changed_with_entity(world, &mut state.query_state, entity)
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        let query = QueryState::<D, F>::new(world);
+        add_access::<Self>(world, access, query.component_access().clone());
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        ReactiveQuery {
+            // SAFETY: The caller ensures `world` has access to the query's data.
+            query: unsafe { get_state(&mut state.query, world) },
+            entities: &mut state.entities,
+        }
+    }
+}
+
+/// State of a [`ReactiveQueryMut`], tracking changes with the read-only version of `D`.
+///
+/// The components the query accesses are kept to check for the reaction's own writes,
+/// and the tick is recorded at the end of each run, so those writes aren't changes.
+pub type ReactiveQueryMutState<D, F> = (
+    ReactiveQueryState<
+        <D as QueryData>::ReadOnly,
+        F,
+        <<D as QueryData>::ReadOnly as ReactiveQueryData<F>>::State,
+    >,
+    SystemState<Query<'static, 'static, (Entity, D), F>>,
+    Vec<ComponentId>,
+    Option<Tick>,
+);
+
+/// Reactive query like [`ReactiveQuery`] that can also mutate its items, like `&mut T`.
+///
+/// Changes are tracked with the read-only version of `D`,
+/// except for writes made by the reaction itself, which don't run it again.
+/// This query has write access to its components, so it can't be combined with
+/// other parameters that access them, like another [`ReactiveQueryMut`] for the same data.
+pub struct ReactiveQueryMut<'w, 's, D: QueryData + 'static, F: QueryFilter + 'static = ()> {
+    query: Query<'w, 's, (Entity, D), F>,
+    entities: &'s mut EntityHashSet,
+}
+
+impl<'w, 's, D: QueryData + 'static, F: QueryFilter + 'static> ReactiveQueryMut<'w, 's, D, F> {
+    /// Get the read-only item for `entity`, tracking it for changes.
+    pub fn get(
+        &mut self,
+        entity: Entity,
+    ) -> Result<<<D as QueryData>::ReadOnly as WorldQuery>::Item<'_>, ReactiveQueryError> {
+        self.entities.insert(entity);
+
+        let (_, item) = self.query.get(entity)?;
+        Ok(item)
+    }
+
+    /// Get the mutable item for `entity`, tracking it for changes.
+    pub fn get_mut(
+        &mut self,
+        entity: Entity,
+    ) -> Result<<D as WorldQuery>::Item<'_>, ReactiveQueryError> {
+        self.entities.insert(entity);
+
+        let (_, item) = self.query.get_mut(entity)?;
+        Ok(item)
+    }
+
+    /// Iterate over all matching mutable items, tracking each entity that is visited.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = <D as WorldQuery>::Item<'_>> {
+        let entities = &mut *self.entities;
+
+        self.query.iter_mut().map(move |(entity, item)| {
+            entities.insert(entity);
+            item
+        })
+    }
+}
+
+/// Outside of reactions, a [`ReactiveQueryMut`] works like a [`Query`],
+/// and the entities it tracks are kept with the system.
+unsafe impl<D: QueryData + 'static, F: QueryFilter + 'static> SystemParam
+    for ReactiveQueryMut<'_, '_, D, F>
+{
+    type State = (QueryState<(Entity, D), F>, EntityHashSet);
+
+    type Item<'world, 'state> = ReactiveQueryMut<'world, 'state, D, F>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        (
+            Query::<(Entity, D), F>::init_state(world, system_meta),
+            EntityHashSet::default(),
+        )
+    }
+
+    unsafe fn new_archetype(
+        state: &mut Self::State,
+        archetype: &Archetype,
+        system_meta: &mut SystemMeta,
+    ) {
+        // SAFETY: The caller ensures `archetype` is from the world used to initialize `state`.
+        unsafe { Query::<(Entity, D), F>::new_archetype(&mut state.0, archetype, system_meta) }
+    }
+
+    unsafe fn get_param<'world, 'state>(
+        state: &'state mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'world>,
+        change_tick: Tick,
+    ) -> Self::Item<'world, 'state> {
+        let (query, entities) = state;
+
+        ReactiveQueryMut {
+            // SAFETY: The caller ensures `world` has access to the query's data.
+            query: unsafe { Query::get_param(query, system_meta, world, change_tick) },
+            entities,
+        }
+    }
+}
+
+impl<D, F> ReactiveSystemParam for ReactiveQueryMut<'_, '_, D, F>
+where
+    D: QueryData + 'static,
+    D::ReadOnly: ReactiveQueryData<F> + 'static,
+    F: QueryFilter + 'static,
+{
+    type State = ReactiveQueryMutState<D, F>;
+
+    fn init(world: &mut World) -> <Self as ReactiveSystemParam>::State {
+        let query = QueryState::<(Entity, D), F>::new(world);
+        let ids = query
+            .component_access()
+            .access()
+            .reads_and_writes()
+            .collect();
+
+        (
+            ReactiveQuery::<D::ReadOnly, F>::init(world),
+            SystemState::new(world),
+            ids,
+            None,
+        )
+    }
+
+    fn is_changed(
+        mut world: DeferredWorld,
+        state: &mut <Self as ReactiveSystemParam>::State,
+    ) -> bool {
+        let (tracked, _, ids, ran_at) = state;
+
+        // Entities that newly match (or stop matching) the query aren't tracked yet.
+        if tracked.update_matched(&world) || tracked.entities.is_empty() {
+            return true;
+        }
+
+        let ReactiveQueryState {
+            entities,
+            query_state,
+            ..
+        } = tracked;
+        entities.iter().any(|entity| {
+            D::ReadOnly::is_changed_with_entity(world.reborrow(), query_state, *entity)
+                && !is_written_by_run(&world, ids, *entity, *ran_at)
+        })
+    }
+
+    fn is_changed_with_entity(
+        mut world: DeferredWorld,
+        state: &mut <Self as ReactiveSystemParam>::State,
+        entity: Entity,
+    ) -> bool {
+        // A target that this query doesn't read can depend on any tracked entity,
+        // including ones written by this reaction for other targets.
+        if !state.0.entities.contains(&entity) {
+            return ReactiveQuery::<D::ReadOnly, F>::is_changed(world, &mut state.0);
+        }
+
+        // Only check the target itself, so changes to other targets don't run it.
+        D::ReadOnly::is_changed_with_entity(world.reborrow(), &mut state.0.query_state, entity)
+            && !is_written_by_run(&world, &state.2, entity, state.3)
+    }
+
+    fn after_run(world: DeferredWorld, state: &mut <Self as ReactiveSystemParam>::State) {
+        // Writes from this run have the current tick, so anything newer comes from elsewhere.
+        state.3 = Some(world.increment_change_tick());
+    }
+
+    fn init_access(world: &mut World, access: &mut FilteredAccessSet<ComponentId>) {
+        let query = QueryState::<D, F>::new(world);
+        add_access::<Self>(world, access, query.component_access().clone());
+    }
+
+    unsafe fn get<'w: 's, 's>(
+        world: UnsafeWorldCell<'w>,
+        state: &'s mut <Self as ReactiveSystemParam>::State,
+    ) -> Self::Item<'w, 's> {
+        ReactiveQueryMut {
+            // SAFETY: The caller ensures `world` has write access to the query's data.
+            query: unsafe { get_state(&mut state.1, world) },
+            entities: &mut state.0.entities,
+        }
+    }
+}
+
+/// Returns `true` if the components `ids` of `entity` only changed
+/// by the time the reaction last ran at `ran_at`, including its own writes.
+fn is_written_by_run(
+    world: &World,
+    ids: &[ComponentId],
+    entity: Entity,
+    ran_at: Option<Tick>,
+) -> bool {
+    let (Some(ran_at), Some(entity)) = (ran_at, world.get_entity(entity)) else {
+        return false;
+    };
+
+    let this_run = world.read_change_tick();
+    let mut is_written = false;
+    for id in ids {
+        let Some(ticks) = entity.get_change_ticks_by_id(*id) else {
+            continue;
+        };
+        if !ticks.is_changed(world.last_change_tick(), this_run) {
+            continue;
+        }
+        if ticks.last_changed_tick().is_newer_than(ran_at, this_run) {
+            return false;
+        }
+        is_written = true;
+    }
+    is_written
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test::ReactionTestApp, IntoReactiveSystem, Reaction, ReactionPlugin, ReactiveSystem,
+    };
+
+    #[derive(Component)]
+    struct Health(i32);
+
+    #[derive(Default, Resource)]
+    struct Trigger(u32);
+
+    #[derive(Default, Resource)]
+    struct Toggle(bool);
+
+    #[test]
+    #[should_panic(
+        expected = "conflicts with a previous parameter's access to [bevy_mod_reaction::system_param::tests::Health]"
+    )]
+    fn conflicting_access_panics() {
+        let mut world = World::new();
+        let mut system = (|_: In<Scope>, _: ReactiveQueryMut<&mut Health>, _: Query<&Health>| {})
+            .into_reactive_system();
+        system.init(&mut world);
+    }
+
+    #[derive(Component)]
+    struct Armor(i32);
+
+    #[derive(Component)]
+    struct Hit;
+
+    /// Each param gets its item from the same world, so run this with Miri to check for aliasing:
+    /// `cargo +nightly miri test --lib params_share_the_world`.
+    #[test]
+    fn params_share_the_world() {
+        let mut world = World::new();
+        let entity = world.spawn((Health(10), Armor(3))).id();
+        let mut system =
+            (|scope: In<Scope>,
+              mut armor: ReactiveQuery<&Armor>,
+              (mut health, mut commands): (ReactiveQueryMut<&mut Health>, Commands)| {
+                let armor = armor.get(scope.entity).unwrap().0;
+                health.get_mut(scope.entity).unwrap().0 -= armor;
+                commands.entity(scope.entity).insert(Hit);
+            })
+            .into_reactive_system();
+        system.init(&mut world);
+
+        system.run((), DeferredWorld::from(&mut world), entity);
+        world.flush();
+        assert_eq!(world.get::<Health>(entity).unwrap().0, 7);
+        assert!(world.entity(entity).contains::<Hit>());
+    }
+
+    #[test]
+    fn only_changed_targets_run() {
+        let mut app = ReactionTestApp::new();
+        let a = app.spawn_target(Health(10));
+        let b = app.spawn_target(Health(10));
+        let mut reaction = Reaction::new(|scope: In<Scope>, mut query: ReactiveQuery<&Health>| {
+            query.get(scope.entity).unwrap();
+        });
+        reaction.add_target(a).add_target(b);
+        let reaction = app.spawn(reaction);
+
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+
+        app.world_mut().get_mut::<Health>(a).unwrap().0 -= 1;
+        app.step();
+        assert_eq!(app.runs(reaction), 3);
+
+        app.world_mut().get_mut::<Health>(b).unwrap().0 -= 1;
+        app.step();
+        assert_eq!(app.runs(reaction), 4);
+    }
+
+    #[test]
+    fn reactive_query_in_system() {
+        let mut world = World::new();
+        let entity = world.spawn(Health(10)).id();
+
+        let mut system = IntoSystem::into_system(move |mut query: ReactiveQuery<&Health>| {
+            assert_eq!(query.get(entity).unwrap().0, 10);
+            query.contains(entity)
+        });
+        system.initialize(&mut world);
+        assert!(system.run((), &mut world));
+    }
+
+    #[test]
+    fn get_many_tracks_on_success() {
+        let mut world = World::new();
+        let a = world.spawn(Health(1)).id();
+        let b = world.spawn(Health(2)).id();
+        let c = world.spawn(Health(3)).id();
+        let missing = world.spawn_empty().id();
+
+        let mut state = ReactiveQuery::<&Health>::init_filtered::<()>(&mut world);
+        let mut query = state.query(&world);
+
+        assert_eq!(
+            query.get_many([a, missing, c]).map(|_| ()),
+            Err(ReactiveQueryError::QueryDoesNotMatch(missing))
+        );
+        assert_eq!(query.tracked().count(), 0);
+
+        let [x, y, z] = query.get_many([a, b, c]).unwrap();
+        assert_eq!([x.0, y.0, z.0], [1, 2, 3]);
+        let [x, y, _] = query.get_many([a, a, b]).unwrap();
+        assert_eq!([x.0, y.0], [1, 1]);
+        assert!([a, b, c].iter().all(|entity| query.contains(*entity)));
+        assert!(!query.contains(missing));
+    }
+
+    #[test]
+    fn peek_does_not_trigger() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        app.world_mut().init_resource::<Toggle>();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let reaction = app.spawn(Reaction::new(
+            move |_: In<Scope>, _: Res<Trigger>, Peek(toggle): Peek<Res<Toggle>>| {
+                log.lock().unwrap().push(toggle.0);
+            },
+        ));
+
+        app.step();
+        app.world_mut().resource_mut::<Toggle>().0 = true;
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+
+        app.world_mut().resource_mut::<Trigger>().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+        assert_eq!(*seen.lock().unwrap(), [false, true]);
+    }
+
+    #[test]
+    fn peek_in_system() {
+        let mut world = World::new();
+        world.insert_resource(Toggle(true));
+
+        let mut system = IntoSystem::into_system(|Peek(toggle): Peek<Res<Toggle>>| toggle.0);
+        system.initialize(&mut world);
+        assert!(system.run((), &mut world));
+    }
+
+    #[test]
+    fn mutated_ignores_insertion() {
+        let mut app = ReactionTestApp::new();
+        let reaction = app.spawn(Reaction::new(|_: In<Scope>, _: Mutated<Res<Trigger>>| {}));
+        app.world_mut().init_resource::<Trigger>();
+
+        app.step_n(2);
+        assert_eq!(app.runs(reaction), 0);
+
+        app.world_mut().remove_resource::<Trigger>();
+        app.world_mut().insert_resource(Trigger(1));
+        app.step();
+        assert_eq!(app.runs(reaction), 0);
+
+        app.world_mut().resource_mut::<Trigger>().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+    }
+
+    #[test]
+    fn mutated_in_system() {
+        let mut world = World::new();
+        world.insert_resource(Trigger(3));
+
+        let mut system =
+            IntoSystem::into_system(|Mutated(trigger): Mutated<Res<Trigger>>| trigger.0);
+        system.initialize(&mut world);
+        assert_eq!(system.run((), &mut world), 3);
+    }
+
+    #[test]
+    fn reactive_world_runs_every_poll() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().insert_resource(Trigger(2));
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let reaction = app.spawn(Reaction::new(move |_: In<Scope>, world: ReactiveWorld| {
+            log.lock().unwrap().push(world.resource::<Trigger>().0);
+        }));
+
+        app.step_n(3);
+        assert_eq!(app.runs(reaction), 3);
+        assert_eq!(*seen.lock().unwrap(), [2, 2, 2]);
+    }
+
+    #[test]
+    fn reactive_world_in_system() {
+        let mut world = World::new();
+        world.insert_resource(Trigger(4));
+
+        let mut system =
+            IntoSystem::into_system(|world: ReactiveWorld| world.resource::<Trigger>().0);
+        system.initialize(&mut world);
+        assert_eq!(system.run((), &mut world), 4);
+    }
+
+    #[derive(Component)]
+    struct Spawned;
+
+    #[test]
+    fn parallel_commands_in_system() {
+        let mut world = World::new();
+
+        let mut system = IntoSystem::into_system(|commands: ReactiveParallelCommands| {
+            (0..4).for_each(|_| {
+                commands.command_scope(|mut commands| {
+                    commands.spawn(Spawned);
+                });
+            });
+        });
+        system.initialize(&mut world);
+        system.run((), &mut world);
+        system.apply_deferred(&mut world);
+
+        assert_eq!(world.query::<&Spawned>().iter(&world).count(), 4);
+    }
+
+    #[test]
+    fn parallel_commands_in_reaction() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Trigger>();
+        app.spawn(Reaction::new(
+            |_: In<Scope>, _: Res<Trigger>, commands: ReactiveParallelCommands| {
+                commands.command_scope(|mut commands| {
+                    commands.spawn(Spawned);
+                });
+            },
+        ));
+
+        app.step();
+        app.world_mut().resource_mut::<Trigger>().0 += 1;
+        app.step();
+        let count = app
+            .world_mut()
+            .query::<&Spawned>()
+            .iter(app.world())
+            .count();
+        assert_eq!(count, 2);
+    }
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Order(u32);
+
+    #[test]
+    fn parallel_commands_apply_after_earlier_commands() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut system =
+            (|scope: In<Scope>, mut commands: Commands, parallel: ReactiveParallelCommands| {
+                parallel.command_scope(|mut commands| {
+                    commands.entity(scope.entity).insert(Order(2));
+                });
+                commands.entity(scope.entity).insert(Order(1));
+            })
+            .into_reactive_system();
+        system.init(&mut world);
+
+        system.run((), DeferredWorld::from(&mut world), entity);
+        world.flush();
+        assert_eq!(world.get::<Order>(entity), Some(&Order(2)));
+    }
+
+    #[test]
+    fn reactive_query_mut_in_system() {
+        let mut world = World::new();
+        let entity = world.spawn(Health(10)).id();
+
+        let mut system =
+            IntoSystem::into_system(move |mut query: ReactiveQueryMut<&mut Health>| {
+                query.get_mut(entity).unwrap().0 -= 1;
+            });
+        system.initialize(&mut world);
+        system.run((), &mut world);
+        assert_eq!(world.get::<Health>(entity).unwrap().0, 9);
+    }
+
+    fn own_writes_do_not_trigger(plugin: ReactionPlugin) {
+        let mut app = ReactionTestApp::with_plugin(plugin);
+        let entity = app.spawn_target(Health(10));
+        let reaction = app.spawn(Reaction::new(
+            |_: In<Scope>, mut query: ReactiveQueryMut<&mut Health>| {
+                for mut health in query.iter_mut() {
+                    health.0 += 1;
+                }
+            },
+        ));
+
+        app.step_n(3);
+        assert_eq!(app.runs(reaction), 1);
+        assert_eq!(app.world().get::<Health>(entity).unwrap().0, 11);
+
+        app.world_mut().get_mut::<Health>(entity).unwrap().0 = 0;
+        app.step_n(3);
+        assert_eq!(app.runs(reaction), 2);
+        assert_eq!(app.world().get::<Health>(entity).unwrap().0, 1);
+    }
+
+    #[test]
+    fn reactive_query_mut_ignores_own_writes() {
+        own_writes_do_not_trigger(ReactionPlugin::new());
+    }
+
+    #[test]
+    fn reactive_query_mut_settles() {
+        own_writes_do_not_trigger(ReactionPlugin::new().settle());
+    }
+
+    #[test]
+    fn reactive_query_mut_ignores_own_writes_to_targets() {
+        let mut app = ReactionTestApp::with_plugin(ReactionPlugin::new().settle());
+        let a = app.spawn_target(Health(10));
+        let b = app.spawn_target(Health(10));
+        let mut reaction = Reaction::new(
+            |scope: In<Scope>, mut query: ReactiveQueryMut<&mut Health>| {
+                query.get_mut(scope.entity).unwrap().0 += 1;
+            },
+        );
+        reaction.add_target(a).add_target(b);
+        let reaction = app.spawn(reaction);
+
+        app.step_n(3);
+        assert_eq!(app.runs(reaction), 2);
+
+        app.world_mut().get_mut::<Health>(b).unwrap().0 = 0;
+        app.step_n(3);
+        assert_eq!(app.runs(reaction), 3);
+        assert_eq!(app.world().get::<Health>(a).unwrap().0, 11);
+        assert_eq!(app.world().get::<Health>(b).unwrap().0, 1);
+    }
+
+    fn tracked(
+        world: &World,
+        state: &mut <ReactiveQuery<&'static Health> as ReactiveSystemParam>::State,
+    ) -> usize {
+        state.query(world).tracked().count()
+    }
+
+    #[test]
+    fn stale_entities_are_pruned() {
+        let mut world = World::new();
+        let a = world.spawn(Health(1)).id();
+        let b = world.spawn(Health(2)).id();
+        let c = world.spawn(Health(3)).id();
+        let mut state = ReactiveQuery::<&Health>::init(&mut world);
+        let mut query = state.query(&world);
+        for entity in [a, b, c] {
+            query.get(entity).unwrap();
+        }
+        assert_eq!(tracked(&world, &mut state), 3);
+
+        world.despawn(b);
+        world.entity_mut(c).remove::<Health>();
+        assert!(state.is_changed(DeferredWorld::from(&mut world)));
+        assert_eq!(tracked(&world, &mut state), 1);
+
+        // Pruned on every poll, even when nothing else changed.
+        world.spawn(Health(4));
+        state.is_changed(DeferredWorld::from(&mut world));
+        world.despawn(a);
+        state.is_changed(DeferredWorld::from(&mut world));
+        assert_eq!(tracked(&world, &mut state), 0);
+    }
+
+    fn tracked_capacity(
+        world: &World,
+        state: &mut <ReactiveQuery<&'static Health> as ReactiveSystemParam>::State,
+    ) -> usize {
+        state.query(world).tracked_capacity()
+    }
+
+    /// Track 100 entities with `policy`, then despawn all but `remaining` of them,
+    /// returning the capacity of the tracked set.
+    fn capacity_after_pruning(policy: TrackingPolicy, remaining: usize) -> usize {
+        let mut world = World::new();
+        world.insert_resource(policy);
+        let entities: Vec<_> = (0..100).map(|n| world.spawn(Health(n)).id()).collect();
+        let mut state = ReactiveQuery::<&Health>::init(&mut world);
+        assert!(tracked_capacity(&world, &mut state) >= policy.initial_capacity);
+
+        let mut query = state.query(&world);
+        for entity in &entities {
+            query.get(*entity).unwrap();
+        }
+        assert!(tracked_capacity(&world, &mut state) >= 100);
+
+        for entity in &entities[remaining..] {
+            world.despawn(*entity);
+        }
+        state.is_changed(DeferredWorld::from(&mut world));
+        assert_eq!(tracked(&world, &mut state), remaining);
+        tracked_capacity(&world, &mut state)
+    }
+
+    #[test]
+    fn tracked_sets_follow_the_policy() {
+        let policy = |shrink_ratio| TrackingPolicy {
+            initial_capacity: 16,
+            shrink_ratio,
+        };
+        let initial = EntityHashSet::with_capacity_and_hasher(16, Default::default()).capacity();
+
+        // Shrunk once the peak is more than twice the length, but not below the initial capacity.
+        assert_eq!(capacity_after_pruning(policy(2), 10), initial);
+        assert_eq!(capacity_after_pruning(policy(2), 0), initial);
+
+        // Within the ratio, so the set isn't shrunk.
+        assert_eq!(
+            capacity_after_pruning(policy(2), 60),
+            capacity_after_pruning(policy(1000), 60)
+        );
+        assert_ne!(capacity_after_pruning(policy(1000), 10), initial);
+    }
+
+    #[test]
+    fn single_tracks_the_only_entity() {
+        let mut world = World::new();
+        let mut state = ReactiveQuery::<&Health>::init(&mut world);
+        assert!(matches!(
+            state.query(&world).get_single(),
+            Err(QuerySingleError::NoEntities(_))
+        ));
+        assert_eq!(tracked(&world, &mut state), 0);
+
+        let a = world.spawn(Health(1)).id();
+        state.is_changed(DeferredWorld::from(&mut world));
+        assert_eq!(state.query(&world).single().0, 1);
+        assert_eq!(state.query(&world).tracked().collect::<Vec<_>>(), vec![a]);
+
+        world.get_mut::<Health>(a).unwrap().0 = 2;
+        assert!(state.is_changed(DeferredWorld::from(&mut world)));
+
+        // Errors don't track any more entities.
+        world.spawn(Health(3));
+        state.is_changed(DeferredWorld::from(&mut world));
+        assert!(matches!(
+            state.query(&world).get_single(),
+            Err(QuerySingleError::MultipleEntities(_))
+        ));
+        assert_eq!(tracked(&world, &mut state), 1);
     }
 }
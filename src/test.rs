@@ -0,0 +1,168 @@
+//! Helpers for testing reactions, enabled with the `test-util` feature.
+
+use crate::{Reaction, ReactionPlugin, ReactionRegistry};
+use bevy_app::App;
+use bevy_ecs::{prelude::*, schedule::ScheduleLabel};
+#[cfg(feature = "state")]
+use bevy_state::app::StatesPlugin;
+#[cfg(feature = "time")]
+use bevy_time::{TimePlugin, TimeUpdateStrategy};
+#[cfg(feature = "time")]
+use std::time::Duration;
+
+/// App for testing reactions, with a [`ReactionPlugin`] and the run count of each reaction.
+///
+/// Time and states are included with the `time` and `state` features,
+/// so reactions with a TTL, an interval or a state can be tested.
+/// Time only moves forward with [`ReactionTestApp::advance`].
+pub struct ReactionTestApp {
+    app: App,
+}
+
+impl ReactionTestApp {
+    /// Create a new test app with the default [`ReactionPlugin`].
+    pub fn new() -> Self {
+        Self::with_plugin(ReactionPlugin::default())
+    }
+
+    /// Create a new test app with `plugin`.
+    pub fn with_plugin(plugin: ReactionPlugin) -> Self {
+        let mut app = App::new();
+        #[cfg(feature = "time")]
+        app.add_plugins(TimePlugin)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+        #[cfg(feature = "state")]
+        app.add_plugins(StatesPlugin);
+        app.add_plugins(plugin);
+        Self { app }
+    }
+
+    /// Spawn `reaction`, returning its entity.
+    ///
+    /// The reaction is initialized on the next step.
+    pub fn spawn<L: ScheduleLabel>(&mut self, reaction: Reaction<L>) -> Entity {
+        self.app.world_mut().spawn(reaction).id()
+    }
+
+    /// Spawn an entity with `bundle`, like a target for a reaction.
+    pub fn spawn_target(&mut self, bundle: impl Bundle) -> Entity {
+        self.app.world_mut().spawn(bundle).id()
+    }
+
+    /// Run the app for one frame.
+    pub fn step(&mut self) -> &mut Self {
+        self.app.update();
+        self
+    }
+
+    /// Run the app for `frames` frames.
+    pub fn step_n(&mut self, frames: usize) -> &mut Self {
+        for _ in 0..frames {
+            self.app.update();
+        }
+        self
+    }
+
+    /// Run the app for one frame, advancing time by `duration`.
+    ///
+    /// [`Time`](bevy_time::Time) advances by at most its max delta in a single frame.
+    #[cfg(feature = "time")]
+    pub fn advance(&mut self, duration: Duration) -> &mut Self {
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(duration));
+        self.app.update();
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+        self
+    }
+
+    /// Returns the number of times the reaction of `entity` ran, counting each target.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `entity` has no reaction.
+    pub fn runs(&self, entity: Entity) -> usize {
+        self.world()
+            .resource::<ReactionRegistry>()
+            .get(entity)
+            .expect("Entity has a reaction")
+            .run_count()
+    }
+
+    /// Returns the world of the app.
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    /// Returns the world of the app mutably, to make changes between steps.
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    /// Returns the app.
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    /// Returns the app mutably, to add plugins or systems.
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+}
+
+impl Default for ReactionTestApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scope;
+
+    #[derive(Default, Resource)]
+    struct Counter(u32);
+
+    #[test]
+    fn counts_runs() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Counter>();
+        let reaction = app.spawn(Reaction::new(|_: In<Scope>, _: Res<Counter>| {}));
+
+        app.step();
+        assert_eq!(app.runs(reaction), 1);
+
+        app.step_n(3);
+        assert_eq!(app.runs(reaction), 1);
+
+        app.world_mut().resource_mut::<Counter>().0 += 1;
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+    }
+
+    #[test]
+    fn counts_runs_for_each_target() {
+        let mut app = ReactionTestApp::new();
+        app.world_mut().init_resource::<Counter>();
+        let a = app.spawn_target(());
+        let b = app.spawn_target(());
+        let mut reaction = Reaction::new(|_: In<Scope>, _: Res<Counter>| {});
+        reaction.add_target(a).add_target(b);
+        let reaction = app.spawn(reaction);
+
+        app.step();
+        assert_eq!(app.runs(reaction), 2);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn advances_time() {
+        let mut app = ReactionTestApp::new();
+        app.step().advance(Duration::from_millis(100));
+        assert_eq!(
+            app.world().resource::<bevy_time::Time>().elapsed(),
+            Duration::from_millis(100)
+        );
+    }
+}
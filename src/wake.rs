@@ -0,0 +1,55 @@
+use bevy_ecs::{component::ComponentId, entity::EntityHashSet, prelude::*};
+use bevy_utils::HashMap;
+
+/// Reactions that are subscribed to inserts of each component.
+///
+/// Used by [`react_observed`](crate::react_observed) to only run reactions that were woken up.
+#[derive(Default, Resource)]
+pub struct ReactionSubscriptions {
+    subscribers: HashMap<ComponentId, Vec<Entity>>,
+    pub(crate) woken: EntityHashSet,
+}
+
+impl ReactionSubscriptions {
+    /// Wake every reaction subscribed to the component `id`.
+    pub fn wake(&mut self, id: ComponentId) {
+        if let Some(subscribers) = self.subscribers.get(&id) {
+            self.woken.extend(subscribers.iter().copied());
+        }
+    }
+
+    /// Remove `entity` from every subscription.
+    pub(crate) fn unsubscribe(&mut self, entity: Entity) {
+        for subscribers in self.subscribers.values_mut() {
+            subscribers.retain(|subscriber| *subscriber != entity);
+        }
+        self.woken.remove(&entity);
+    }
+}
+
+/// Marker for reactions that are only run when woken up by [`ReactionSubscriptions`].
+#[derive(Component)]
+pub struct Subscribed;
+
+/// Subscribe the reaction `entity` to inserts of `T`.
+pub(crate) fn subscribe<T: Component>(world: &mut World, entity: Entity) {
+    let id = world.init_component::<T>();
+    let mut subscriptions = world.get_resource_or_insert_with(ReactionSubscriptions::default);
+
+    // Only observe each component once, no matter how many reactions subscribe to it.
+    let is_observed = subscriptions.subscribers.contains_key(&id);
+    subscriptions
+        .subscribers
+        .entry(id)
+        .or_default()
+        .push(entity);
+    subscriptions.woken.insert(entity);
+
+    if !is_observed {
+        world.observe(
+            move |_: Trigger<OnInsert, T>, mut subscriptions: ResMut<ReactionSubscriptions>| {
+                subscriptions.wake(id);
+            },
+        );
+    }
+}
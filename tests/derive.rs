@@ -0,0 +1,87 @@
+//! Reactions with derived `ReactiveSystemParam`s and `ReactiveQueryData`.
+
+use bevy_app::App;
+use bevy_ecs::{prelude::*, query::QueryData, system::SystemParam};
+use bevy_mod_reaction::{Reaction, ReactionPlugin, ReactiveQueryData, ReactiveSystemParam, Scope};
+
+#[derive(Component)]
+struct Health(i32);
+
+#[derive(Component)]
+struct Armor(i32);
+
+#[derive(Component, Debug, PartialEq)]
+struct Damage(i32);
+
+#[derive(SystemParam, ReactiveSystemParam)]
+struct Healths<'w, 's> {
+    query: Query<'w, 's, &'static Health>,
+    commands: Commands<'w, 's>,
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(ReactionPlugin::new());
+    app
+}
+
+#[test]
+fn derived_params_combine_their_fields() {
+    let mut app = app();
+    let target = app.world_mut().spawn(Health(100)).id();
+    let mut reaction = Reaction::new(|scope: In<Scope>, mut healths: Healths| {
+        let health = healths.query.get(scope.entity).unwrap().0;
+        healths
+            .commands
+            .entity(scope.entity)
+            .insert(Damage(100 - health));
+    });
+    reaction.add_target(target);
+    app.world_mut().spawn(reaction);
+
+    app.update();
+    assert_eq!(app.world().get::<Damage>(target), Some(&Damage(0)));
+
+    // The query field changes, so the reaction runs and its commands are applied.
+    app.world_mut().get_mut::<Health>(target).unwrap().0 = 60;
+    app.update();
+    assert_eq!(app.world().get::<Damage>(target), Some(&Damage(40)));
+
+    // Its own command doesn't change the query, so it doesn't run again.
+    app.world_mut().get_mut::<Damage>(target).unwrap().0 = 0;
+    app.update();
+    assert_eq!(app.world().get::<Damage>(target), Some(&Damage(0)));
+}
+
+#[derive(QueryData, ReactiveQueryData)]
+struct Stats {
+    health: &'static Health,
+    armor: &'static Armor,
+}
+
+#[test]
+fn derived_query_data_changes_with_each_field() {
+    let mut app = app();
+    let target = app.world_mut().spawn((Health(100), Armor(10))).id();
+    let mut reaction = Reaction::new(
+        |scope: In<Scope>, query: Query<Stats>, mut commands: Commands| {
+            let stats = query.get(scope.entity).unwrap();
+            commands
+                .entity(scope.entity)
+                .insert(Damage(stats.health.0 - stats.armor.0));
+        },
+    );
+    reaction.add_target(target);
+    app.world_mut().spawn(reaction);
+
+    app.update();
+    assert_eq!(app.world().get::<Damage>(target), Some(&Damage(90)));
+
+    app.world_mut().get_mut::<Health>(target).unwrap().0 = 60;
+    app.update();
+    assert_eq!(app.world().get::<Damage>(target), Some(&Damage(50)));
+
+    app.world_mut().get_mut::<Armor>(target).unwrap().0 = 20;
+    app.update();
+    assert_eq!(app.world().get::<Damage>(target), Some(&Damage(40)));
+}
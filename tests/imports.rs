@@ -0,0 +1,50 @@
+//! The public API, imported from the crate root.
+
+// Each import fails to compile if it moves out of the crate root.
+#[allow(unused_imports)]
+use bevy_mod_reaction::{
+    box_reactive, changed, combine_latest, init_state, react, react_exit, react_observed,
+    react_parallel, react_settle, react_shared, Accumulate, AccumulatedDelta,
+    AccumulatedDeltaState, AndThen, AndThenOk, AsyncReaction, BoxedReactiveSystem, Chain,
+    ChangedAny, ChangedAnyState, CombineLatest, ComponentQueryState, ComponentTuple, DiffBundle,
+    DirtyWhen, FanOut, FilterQueryState, FromWorldSystem, FunctionReactiveSystem, GroupKey,
+    HasQueryState, Hashed, HashedState, HierarchyState, IntoLatestSources, IntoReactiveSystem,
+    LatestSources, Map, MapErr, MapInput, MapOpt, Mutated, OnChanged, OnRemoved, OptionQueryState,
+    Peek, Reaction, ReactionDiagnostics, ReactionError, ReactionErrorHandler, ReactionHandle,
+    ReactionMessages, ReactionPlugin, ReactionReceiver, ReactionRegistry, ReactionSender,
+    ReactionSubscriptions, ReactiveChildren, ReactiveDescendants, ReactiveDescendantsState,
+    ReactiveParallelCommands, ReactiveParent, ReactiveQuery, ReactiveQueryCache, ReactiveQueryData,
+    ReactiveQueryError, ReactiveQueryMut, ReactiveQueryMutState, ReactiveQueryState,
+    ReactiveResources, ReactiveSystem, ReactiveSystemParam, ReactiveSystemParamFunction,
+    ReactiveWorld, RefQueryState, ResourceTuple, RunOnce, Scope, Selector, SelectorId, Selectors,
+    SharedReaction, Subscribed, Take, TrackingPolicy, WithEntity, WithInput,
+};
+
+#[cfg(feature = "bevy_asset")]
+#[allow(unused_imports)]
+use bevy_mod_reaction::{ReactiveAssetEvents, ReactiveAssetEventsState};
+
+#[cfg(feature = "time")]
+#[allow(unused_imports)]
+use bevy_mod_reaction::Every;
+
+use bevy_ecs::prelude::*;
+
+#[derive(Component)]
+struct Health;
+
+fn assert_param<P: ReactiveSystemParam>() {}
+
+#[test]
+fn root_params_implement_the_root_trait() {
+    // There is a single `ReactiveSystemParam`, so params from any module implement it.
+    assert_param::<ReactiveQuery<&Health>>();
+    assert_param::<ReactiveQueryMut<&mut Health>>();
+    assert_param::<ReactiveWorld>();
+    assert_param::<Res<ReactionRegistry>>();
+
+    let mut world = World::new();
+    world.spawn(Reaction::new(
+        |_: In<Scope>, _: ReactiveQuery<&Health>, _: ReactiveWorld| {},
+    ));
+}